@@ -4,7 +4,10 @@ use clap::{Args, ValueEnum};
 use raiko_lib::{
     consts::VerifierType,
     input::{BlobProofType, GuestInput, GuestOutput},
-    primitives::eip4844::{calc_kzg_proof, commitment_to_version_hash, kzg_proof_to_bytes},
+    primitives::eip4844::{
+        calc_kzg_proof, commitment_to_version_hash, kzg_proof_to_bytes, proof_of_equivalence,
+        verify_kzg_proof, KzgCommitment, KzgGroup,
+    },
     prover::{IdStore, IdWrite, Proof, ProofKey, Prover, ProverError},
 };
 use reth_primitives::hex;
@@ -12,6 +15,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_with::{serde_as, DisplayFromStr};
 use std::{collections::HashMap, path::Path, str::FromStr};
+use tracing::info;
 use utoipa::ToSchema;
 
 #[derive(Debug, thiserror::Error, ToSchema)]
@@ -175,10 +179,23 @@ impl ProofType {
         store: Option<&mut dyn IdWrite>,
     ) -> RaikoResult<Proof> {
         let mut proof = match self {
-            ProofType::Native => NativeProver::run(input.clone(), output, config, store)
-                .await
-                .map_err(<ProverError as Into<RaikoError>>::into),
+            ProofType::Native => {
+                info!(
+                    "Proving block {} with the {} prover",
+                    output.header.number,
+                    NativeProver::NAME
+                );
+                NativeProver::run(input.clone(), output, config, store)
+                    .await
+                    .map_err(<ProverError as Into<RaikoError>>::into)
+            }
             ProofType::Sp1 => {
+                #[cfg(feature = "sp1")]
+                info!(
+                    "Proving block {} with the {} prover",
+                    output.header.number,
+                    sp1_driver::Sp1Prover::NAME
+                );
                 #[cfg(feature = "sp1")]
                 return sp1_driver::Sp1Prover::run(input.clone(), output, config, store)
                     .await
@@ -187,6 +204,12 @@ impl ProofType {
                 Err(RaikoError::FeatureNotSupportedError(*self))
             }
             ProofType::Risc0 => {
+                #[cfg(feature = "risc0")]
+                info!(
+                    "Proving block {} with the {} prover",
+                    output.header.number,
+                    risc0_driver::Risc0Prover::NAME
+                );
                 #[cfg(feature = "risc0")]
                 return risc0_driver::Risc0Prover::run(input.clone(), output, config, store)
                     .await
@@ -195,6 +218,12 @@ impl ProofType {
                 Err(RaikoError::FeatureNotSupportedError(*self))
             }
             ProofType::Sgx => {
+                #[cfg(feature = "sgx")]
+                info!(
+                    "Proving block {} with the {} prover",
+                    output.header.number,
+                    sgx_prover::SgxProver::NAME
+                );
                 #[cfg(feature = "sgx")]
                 return sgx_prover::SgxProver::run(input.clone(), output, config, store)
                     .await
@@ -206,16 +235,13 @@ impl ProofType {
 
         // Add the kzg proof to the proof if needed
         if let Some(blob_commitment) = input.taiko.blob_commitment.clone() {
-            let kzg_proof = calc_kzg_proof(
-                &input.taiko.tx_data,
-                &commitment_to_version_hash(&blob_commitment.try_into().map_err(|_| {
-                    RaikoError::Conversion(
-                        "Could not convert blob commitment to version hash".to_owned(),
-                    )
-                })?),
-            )
-            .map_err(|e| anyhow::anyhow!(e))?;
-            proof.kzg_proof = Some(hex::encode(kzg_proof_to_bytes(&kzg_proof)));
+            let blob_commitment: KzgCommitment = blob_commitment.try_into().map_err(|_| {
+                RaikoError::Conversion(
+                    "Could not convert blob commitment to version hash".to_owned(),
+                )
+            })?;
+            let kzg_proof = blob_kzg_proof(&input.taiko.tx_data, &blob_commitment)?;
+            proof.kzg_proof = Some(hex::encode(kzg_proof));
         }
 
         Ok(proof)
@@ -259,6 +285,21 @@ impl ProofType {
     }
 }
 
+/// Computes the kzg proof of the blob, making sure we never hand out a proof of equivalence
+/// that doesn't verify against the commitment.
+fn blob_kzg_proof(blob: &[u8], blob_commitment: &KzgCommitment) -> RaikoResult<KzgGroup> {
+    let versioned_hash = commitment_to_version_hash(blob_commitment);
+    let kzg_proof =
+        kzg_proof_to_bytes(&calc_kzg_proof(blob, &versioned_hash).map_err(|e| anyhow::anyhow!(e))?);
+    let (x, y) = proof_of_equivalence(blob, &versioned_hash).map_err(|e| anyhow::anyhow!(e))?;
+    if !verify_kzg_proof(blob_commitment, &x, &y, &kzg_proof).map_err(|e| anyhow::anyhow!(e))? {
+        return Err(RaikoError::Guest(ProverError::GuestError(
+            "Computed kzg proof does not verify".to_owned(),
+        )));
+    }
+    Ok(kzg_proof)
+}
+
 #[serde_as]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 /// A request for a proof.
@@ -408,3 +449,25 @@ impl TryFrom<ProofRequestOpt> for ProofRequest {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use raiko_lib::primitives::eip4844::{calc_kzg_proof_commitment, BYTES_PER_BLOB};
+
+    use super::*;
+
+    #[test]
+    fn blob_kzg_proof_verifies() {
+        let blob = (0..BYTES_PER_BLOB)
+            .map(|v| (v % 64) as u8)
+            .collect::<Vec<u8>>();
+        let commitment = calc_kzg_proof_commitment(&blob).unwrap();
+        assert!(blob_kzg_proof(&blob, &commitment).is_ok());
+
+        let other_commitment = calc_kzg_proof_commitment(&[0u8; BYTES_PER_BLOB]).unwrap();
+        assert!(matches!(
+            blob_kzg_proof(&blob, &other_commitment),
+            Err(RaikoError::Guest(ProverError::GuestError(_)))
+        ));
+    }
+}
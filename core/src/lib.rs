@@ -67,6 +67,11 @@ impl Raiko {
     }
 
     pub fn get_output(&self, input: &GuestInput) -> RaikoResult<GuestOutput> {
+        // Fail fast on blob data that doesn't match the proposal before building the block
+        input
+            .taiko
+            .validate_blob_version_hash()
+            .map_err(|e| anyhow::anyhow!(e))?;
         let db = create_mem_db(&mut input.clone()).unwrap();
         let mut builder = RethBlockBuilder::new(input, db);
         builder.execute_transactions(false).expect("execute");
@@ -24,6 +24,8 @@ pub struct NativeResponse {
 }
 
 impl Prover for NativeProver {
+    const NAME: &'static str = "native";
+
     async fn run(
         input: GuestInput,
         output: &GuestOutput,
@@ -47,7 +49,7 @@ impl Prover for NativeProver {
             std::fs::write(path, json)?;
         }
 
-        trace!("Running the native prover for input {input:?}");
+        trace!("Running the {} prover for input {input:?}", Self::NAME);
 
         let pi = ProtocolInstance::new(&input, &output.header, VerifierType::None)
             .map_err(|e| ProverError::GuestError(e.to_string()))?;
@@ -68,3 +70,15 @@ impl Prover for NativeProver {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interfaces::ProofType;
+
+    #[test]
+    fn native_prover_name() {
+        assert_eq!(NativeProver::NAME, "native");
+        assert_eq!(NativeProver::NAME, ProofType::Native.to_string());
+    }
+}
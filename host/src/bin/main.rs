@@ -1,5 +1,7 @@
 #![allow(incomplete_features)]
+use once_cell::sync::Lazy;
 use raiko_host::{interfaces::HostResult, server::serve, ProverState};
+use raiko_lib::primitives::eip4844::{kzg_settings_fingerprint, KZG_SETTINGS};
 use std::path::PathBuf;
 use tracing::{debug, info};
 use tracing_appender::{
@@ -22,9 +24,17 @@ async fn main() -> HostResult<()> {
     debug!("Args:\n{:#?}", state.opts);
 
     info!("Supported chains: {:?}", state.chain_specs);
+    info!(
+        "KZG trusted setup fingerprint: {}",
+        kzg_settings_fingerprint()
+    );
     info!("Start config:\n{:#?}", state.opts.proof_request_opt);
     info!("Args:\n{:#?}", state.opts);
 
+    // Load the trusted setup before serving, so the first requests don't pay for it
+    Lazy::force(&KZG_SETTINGS);
+    info!("KZG trusted setup loaded, accepting requests");
+
     serve(state).await?;
     Ok(())
 }
@@ -2,8 +2,8 @@ use std::time::Duration;
 
 use lazy_static::lazy_static;
 use prometheus::{
-    labels, register_histogram_vec, register_int_counter_vec, register_int_gauge, HistogramVec,
-    IntCounterVec, IntGauge,
+    exponential_buckets, labels, register_histogram, register_histogram_vec,
+    register_int_counter_vec, register_int_gauge, Histogram, HistogramVec, IntCounterVec, IntGauge,
 };
 use raiko_core::interfaces::ProofType;
 
@@ -56,6 +56,13 @@ lazy_static! {
         &["block_id", "success"]
     )
     .unwrap();
+    pub static ref GUEST_INPUT_SIZE: Histogram = register_histogram!(
+        "guest_input_size_histogram",
+        "serialized size in bytes of the guest inputs",
+        // 16 KiB up to 256 MiB
+        exponential_buckets(16_384.0, 4.0, 8).unwrap()
+    )
+    .unwrap();
     pub static ref CONCURRENT_REQUESTS: IntGauge = register_int_gauge!(
         "concurrent_requests",
         "number of requests currently being processed"
@@ -167,3 +174,30 @@ pub fn observe_total_time(block_id: u64, time: Duration, success: bool) {
     };
     TOTAL_TIME.with(&labels).observe(duration_to_f64(time));
 }
+
+/// Observe the serialized size of a guest input.
+pub fn observe_input_size(size: usize) {
+    GUEST_INPUT_SIZE.observe(size as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_size_histogram_buckets() {
+        let before = GUEST_INPUT_SIZE.get_sample_count();
+        for size in [1_000, 20_000, 20_000, 10_000_000] {
+            observe_input_size(size);
+        }
+        assert_eq!(GUEST_INPUT_SIZE.get_sample_count() - before, 4);
+
+        let metric = GUEST_INPUT_SIZE.metric();
+        let buckets = metric.get_histogram().get_bucket();
+        assert_eq!(buckets.len(), 8);
+        assert_eq!(buckets[0].get_upper_bound(), 16_384.0);
+        assert!(buckets[0].get_cumulative_count() >= 1);
+        assert!(buckets[1].get_cumulative_count() >= 3);
+        assert!(buckets[7].get_cumulative_count() >= 4);
+    }
+}
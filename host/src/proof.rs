@@ -23,7 +23,7 @@ use crate::{
     interfaces::{HostError, HostResult},
     memory,
     metrics::{
-        inc_guest_error, inc_guest_success, inc_host_error, observe_guest_time,
+        inc_guest_error, inc_guest_success, inc_host_error, observe_guest_time, observe_input_size,
         observe_prepare_input_time, observe_total_time,
     },
     Message, Opts,
@@ -233,6 +233,10 @@ pub async fn handle_proof(
             input
         }
     };
+    if let Ok(size) = bincode::serialized_size(&input) {
+        observe_input_size(size as usize);
+    }
+
     memory::reset_stats();
     let output = raiko.get_output(&input)?;
     memory::print_stats("Guest program peak memory used: ");
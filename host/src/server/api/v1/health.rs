@@ -1,27 +1,41 @@
-use axum::{debug_handler, http::StatusCode, routing::get, Router};
-use utoipa::OpenApi;
+use alloy_primitives::B256;
+use axum::{debug_handler, routing::get, Json, Router};
+use raiko_lib::primitives::eip4844::kzg_settings_fingerprint;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
 
 use crate::ProverState;
 
+#[derive(Debug, Serialize, ToSchema)]
+/// The response body of a health check.
+pub struct HealthResponse {
+    #[schema(value_type = String)]
+    /// Fingerprint of the compiled-in KZG trusted setup.
+    pub kzg_settings_fingerprint: B256,
+}
+
 #[utoipa::path(
     get,
     path = "/health",
     tag = "Health",
     responses (
-        (status = 200, description = "Proverd server is healthy"),
+        (status = 200, description = "Proverd server is healthy", body = HealthResponse),
     )
 )]
 #[debug_handler(state = ProverState)]
 /// Health check
 ///
-/// Currently only responds with an OK status.
+/// Responds with an OK status and the fingerprint of the compiled-in KZG trusted setup, so
+/// operators can check which setup the server was built with.
 /// Will return more detailed status information soon.
-async fn health_handler() -> StatusCode {
-    StatusCode::OK
+async fn health_handler() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        kzg_settings_fingerprint: kzg_settings_fingerprint(),
+    })
 }
 
 #[derive(OpenApi)]
-#[openapi(paths(health_handler))]
+#[openapi(paths(health_handler), components(schemas(HealthResponse)))]
 struct Docs;
 
 pub fn create_docs() -> utoipa::openapi::OpenApi {
@@ -25,6 +25,7 @@ use crate::{interfaces::HostResult, ProverState};
 /// - guest_proof_time_histogram - time taken for proof generation by this guest
 /// - prepare_input_time_histogram - time taken for prepare input
 /// - total_time_histogram - time taken for the whole proof request
+/// - guest_input_size_histogram - serialized size in bytes of the guest inputs
 /// - process_cpu_seconds_total - total user and system CPU time spent in seconds
 /// - process_open_fds - number of open file descriptors
 /// - process_max_fds - maximum number of open file descriptors
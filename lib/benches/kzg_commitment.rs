@@ -0,0 +1,31 @@
+#![feature(test)]
+extern crate test;
+use raiko_lib::primitives::eip4844::{calc_kzg_proof_commitment, BYTES_PER_BLOB};
+use test::Bencher;
+
+/// A blob with `fill` bytes of data, zero padded to the full blob size.
+fn blob(fill: usize) -> Vec<u8> {
+    let mut blob = (0..fill).map(|v| (v % 64) as u8).collect::<Vec<u8>>();
+    blob.resize(BYTES_PER_BLOB, 0);
+    blob
+}
+
+fn bench_commitment(b: &mut Bencher, fill: usize) {
+    let blob = blob(fill);
+    b.iter(|| calc_kzg_proof_commitment(&blob).unwrap());
+}
+
+#[bench]
+fn bench_commitment_1kb(b: &mut Bencher) {
+    bench_commitment(b, 1024);
+}
+
+#[bench]
+fn bench_commitment_32kb(b: &mut Bencher) {
+    bench_commitment(b, 32 * 1024);
+}
+
+#[bench]
+fn bench_commitment_128kb(b: &mut Bencher) {
+    bench_commitment(b, BYTES_PER_BLOB);
+}
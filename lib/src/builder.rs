@@ -25,22 +25,85 @@ use reth_primitives::revm_primitives::{
 use reth_primitives::{Address, BlockWithSenders, Header, B256, KECCAK_EMPTY, U256};
 use tracing::debug;
 
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum AncestorChainError {
+    #[error("Invalid chain: ancestor {index} ({number}) is not the parent of the previous header")]
+    BrokenLink { index: usize, number: u64 },
+    #[error(
+        "Invalid chain: ancestor {index} ({number}) is not one of the {MAX_BLOCK_HASH_AGE} most recent blocks"
+    )]
+    TooOld { index: usize, number: u64 },
+    #[error("Invalid chain: {count} ancestor headers exceed the limit of {max}")]
+    TooManyAncestors { count: usize, max: usize },
+}
+
+/// Checks that the ancestor headers link up to the parent header one by one, newest first,
+/// and that all of them are within the `BLOCKHASH` window. Returns the ancestor hashes.
+pub fn verify_ancestor_chain(
+    parent: &Header,
+    ancestors: &[Header],
+) -> Result<Vec<B256>, AncestorChainError> {
+    // Anything beyond the `BLOCKHASH` window can never be valid
+    verify_ancestor_chain_with_limit(parent, ancestors, MAX_BLOCK_HASH_AGE as usize)
+}
+
+/// Same as [`verify_ancestor_chain`], but rejects more than `max` ancestors before hashing
+/// any of them.
+pub fn verify_ancestor_chain_with_limit(
+    parent: &Header,
+    ancestors: &[Header],
+    max: usize,
+) -> Result<Vec<B256>, AncestorChainError> {
+    if ancestors.len() > max {
+        return Err(AncestorChainError::TooManyAncestors {
+            count: ancestors.len(),
+            max,
+        });
+    }
+    let mut hashes = Vec::with_capacity(ancestors.len());
+    let mut prev = parent;
+    for (index, current) in ancestors.iter().enumerate() {
+        let current_hash = current.hash_slow();
+        if prev.parent_hash != current_hash {
+            return Err(AncestorChainError::BrokenLink {
+                index,
+                number: current.number,
+            });
+        }
+        if parent.number < current.number || parent.number - current.number >= MAX_BLOCK_HASH_AGE {
+            return Err(AncestorChainError::TooOld {
+                index,
+                number: current.number,
+            });
+        }
+        hashes.push(current_hash);
+        prev = current;
+    }
+    Ok(hashes)
+}
+
 pub fn calculate_block_header(input: &GuestInput) -> Header {
+    try_calculate_block_header(input).expect("execute")
+}
+
+/// Builds the block from the input like [`calculate_block_header`], returning an error
+/// instead of panicking when the input is invalid.
+pub fn try_calculate_block_header(input: &GuestInput) -> Result<Header> {
     let cycle_tracker = CycleTracker::start("initialize_database");
-    let db = create_mem_db(&mut input.clone()).unwrap();
+    let db = create_mem_db(&mut input.clone())?;
     cycle_tracker.end();
 
     let mut builder = RethBlockBuilder::new(input, db);
 
     let cycle_tracker = CycleTracker::start("execute_transactions");
-    builder.execute_transactions(false).expect("execute");
+    builder.execute_transactions(false)?;
     cycle_tracker.end();
 
     let cycle_tracker = CycleTracker::start("finalize");
-    let header = builder.finalize().expect("execute");
+    let header = builder.finalize()?;
     cycle_tracker.end();
 
-    header
+    Ok(header)
 }
 
 /// Optimistic database
@@ -349,26 +412,9 @@ pub fn create_mem_db(input: &mut GuestInput) -> Result<MemDb> {
     // prepare block hash history
     let mut block_hashes = HashMap::with_capacity(input.ancestor_headers.len() + 1);
     block_hashes.insert(input.parent_header.number, input.parent_header.hash_slow());
-    let mut prev = &input.parent_header;
-    for current in &input.ancestor_headers {
-        let current_hash = current.hash_slow();
-        if prev.parent_hash != current_hash {
-            bail!(
-                "Invalid chain: {} is not the parent of {}",
-                current.number,
-                prev.number
-            );
-        }
-        if input.parent_header.number < current.number
-            || input.parent_header.number - current.number >= MAX_BLOCK_HASH_AGE
-        {
-            bail!(
-                "Invalid chain: {} is not one of the {MAX_BLOCK_HASH_AGE} most recent blocks",
-                current.number,
-            );
-        }
-        block_hashes.insert(current.number, current_hash);
-        prev = current;
+    let ancestor_hashes = verify_ancestor_chain(&input.parent_header, &input.ancestor_headers)?;
+    for (current, hash) in input.ancestor_headers.iter().zip(ancestor_hashes) {
+        block_hashes.insert(current.number, hash);
     }
 
     // Store database
@@ -377,3 +423,54 @@ pub fn create_mem_db(input: &mut GuestInput) -> Result<MemDb> {
         block_hashes,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(len: u64) -> (Header, Vec<Header>) {
+        let mut headers = vec![Header {
+            number: 100 - len,
+            ..Default::default()
+        }];
+        for number in (100 - len + 1)..=100 {
+            let parent_hash = headers.last().unwrap().hash_slow();
+            headers.push(Header {
+                number,
+                parent_hash,
+                ..Default::default()
+            });
+        }
+        let parent = headers.pop().unwrap();
+        headers.reverse();
+        (parent, headers)
+    }
+
+    #[test]
+    fn ancestor_chain() {
+        let (parent, ancestors) = chain(3);
+        let hashes = verify_ancestor_chain(&parent, &ancestors).unwrap();
+        assert_eq!(hashes[0], parent.parent_hash);
+        assert_eq!(hashes.len(), 3);
+
+        let mut broken = ancestors.clone();
+        broken[1].gas_limit = 1;
+        assert_eq!(
+            verify_ancestor_chain(&parent, &broken),
+            Err(AncestorChainError::BrokenLink {
+                index: 1,
+                number: 98
+            })
+        );
+    }
+
+    #[test]
+    fn ancestor_chain_limit() {
+        let (parent, ancestors) = chain(3);
+        assert!(verify_ancestor_chain_with_limit(&parent, &ancestors, 3).is_ok());
+        assert_eq!(
+            verify_ancestor_chain_with_limit(&parent, &ancestors, 2),
+            Err(AncestorChainError::TooManyAncestors { count: 3, max: 2 })
+        );
+    }
+}
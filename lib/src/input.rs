@@ -1,4 +1,5 @@
 use core::{fmt::Debug, str::FromStr};
+use std::collections::BTreeMap;
 #[cfg(feature = "std")]
 use std::path::PathBuf;
 
@@ -13,7 +14,14 @@ use serde_with::serde_as;
 
 #[cfg(not(feature = "std"))]
 use crate::no_std::*;
-use crate::{consts::ChainSpec, primitives::mpt::MptNode, utils::zlib_compress_data};
+use crate::{
+    consts::ChainSpec,
+    primitives::{
+        eip4844::{self, Eip4844Error},
+        mpt::MptNode,
+    },
+    utils::zlib_compress_data,
+};
 
 /// Represents the state of an account's storage.
 /// The storage trie together with the used storage slots allow us to reconstruct all the
@@ -69,6 +77,43 @@ pub struct TaikoGuestInput {
     pub blob_proof_type: BlobProofType,
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum BlobDataError {
+    #[error("Blob is used but no blob data was provided")]
+    MissingBlobData,
+    #[error("Blob is used but no blob commitment was provided")]
+    MissingBlobCommitment,
+    #[error("Blob commitment was provided but the tx list is not blob data")]
+    UnexpectedBlobCommitment,
+}
+
+impl TaikoGuestInput {
+    /// Checks that the blob data and commitment agree with the `blobUsed` flag of the proposal.
+    pub fn validate_blob_data(&self) -> Result<(), BlobDataError> {
+        if self.block_proposed.meta.blobUsed {
+            if self.tx_data.is_empty() {
+                return Err(BlobDataError::MissingBlobData);
+            }
+            if self.blob_commitment.is_none() {
+                return Err(BlobDataError::MissingBlobCommitment);
+            }
+        } else if self.blob_commitment.is_some() {
+            return Err(BlobDataError::UnexpectedBlobCommitment);
+        }
+        Ok(())
+    }
+
+    /// Checks that the blob data hashes to the versioned hash claimed by the proposal.
+    ///
+    /// Calldata tx lists have nothing to check.
+    pub fn validate_blob_version_hash(&self) -> Result<(), Eip4844Error> {
+        if !self.block_proposed.meta.blobUsed {
+            return Ok(());
+        }
+        eip4844::verify_blob_against_version_hash(&self.tx_data, &self.block_proposed.meta.blobHash)
+    }
+}
+
 pub struct ZlibCompressError(pub String);
 
 impl TryFrom<Vec<TransactionSigned>> for TaikoGuestInput {
@@ -204,6 +249,36 @@ sol! {
     function proveBlock(uint64 blockId, bytes calldata input) {}
 }
 
+/// Deterministic encoding of the input, suitable for hashing.
+///
+/// This is the bincode encoding of the input with `parent_storage` sorted by address, so
+/// the result doesn't depend on the iteration order of the hash map. The input is
+/// destructured so new fields have to be added here explicitly.
+pub fn canonical_bytes(input: &GuestInput) -> Vec<u8> {
+    let GuestInput {
+        block,
+        chain_spec,
+        parent_header,
+        parent_state_trie,
+        parent_storage,
+        contracts,
+        ancestor_headers,
+        taiko,
+    } = input;
+    let parent_storage = parent_storage.iter().collect::<BTreeMap<_, _>>();
+    bincode::serialize(&(
+        block,
+        chain_spec,
+        parent_header,
+        parent_state_trie,
+        parent_storage,
+        contracts,
+        ancestor_headers,
+        taiko,
+    ))
+    .expect("guest input is serializable")
+}
+
 #[cfg(feature = "std")]
 use std::path::Path;
 
@@ -213,13 +288,86 @@ pub fn get_input_path(dir: &Path, block_number: u64, network: &str) -> PathBuf {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     extern crate alloc;
     use super::*;
 
+    /// Two inputs holding the same parent storage, inserted in opposite orders.
+    pub(crate) fn reordered_storage_inputs() -> (GuestInput, GuestInput) {
+        let entries = (0u8..8)
+            .map(|i| {
+                (
+                    Address::repeat_byte(i),
+                    (MptNode::default(), vec![U256::from(i)]),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut a = GuestInput::default();
+        let mut b = GuestInput::default();
+        a.parent_storage.extend(entries.iter().cloned());
+        b.parent_storage.extend(entries.iter().rev().cloned());
+        (a, b)
+    }
+
     #[test]
     fn input_serde_roundtrip() {
         let input = GuestInput::default();
         let _: GuestInput = bincode::deserialize(&bincode::serialize(&input).unwrap()).unwrap();
     }
+
+    #[test]
+    fn canonical_bytes_ignore_storage_order() {
+        let (a, mut b) = reordered_storage_inputs();
+        assert_eq!(canonical_bytes(&a), canonical_bytes(&b));
+
+        b.contracts.push(Bytes::from_static(&[1]));
+        assert_ne!(canonical_bytes(&a), canonical_bytes(&b));
+    }
+
+    #[test]
+    fn validate_blob_data() {
+        let mut taiko = TaikoGuestInput::default();
+        assert!(taiko.validate_blob_data().is_ok());
+
+        taiko.blob_commitment = Some(vec![0u8; 48]);
+        assert!(matches!(
+            taiko.validate_blob_data(),
+            Err(BlobDataError::UnexpectedBlobCommitment)
+        ));
+
+        taiko.block_proposed.meta.blobUsed = true;
+        assert!(matches!(
+            taiko.validate_blob_data(),
+            Err(BlobDataError::MissingBlobData)
+        ));
+
+        taiko.tx_data = vec![0u8; eip4844::BYTES_PER_BLOB];
+        taiko.blob_commitment = None;
+        assert!(matches!(
+            taiko.validate_blob_data(),
+            Err(BlobDataError::MissingBlobCommitment)
+        ));
+
+        taiko.blob_commitment = Some(vec![0u8; 48]);
+        assert!(taiko.validate_blob_data().is_ok());
+    }
+
+    #[test]
+    fn validate_blob_version_hash() {
+        let mut taiko = TaikoGuestInput::default();
+        taiko.block_proposed.meta.blobHash = B256::repeat_byte(1);
+        assert!(taiko.validate_blob_version_hash().is_ok());
+
+        taiko.block_proposed.meta.blobUsed = true;
+        taiko.tx_data = vec![0u8; eip4844::BYTES_PER_BLOB];
+        assert!(matches!(
+            taiko.validate_blob_version_hash(),
+            Err(Eip4844Error::VersionHashMismatch { .. })
+        ));
+
+        let commitment = eip4844::calc_kzg_proof_commitment(&taiko.tx_data).unwrap();
+        taiko.block_proposed.meta.blobHash = eip4844::commitment_to_version_hash(&commitment);
+        assert!(taiko.validate_blob_version_hash().is_ok());
+    }
 }
@@ -1,15 +1,18 @@
+use alloy_sol_types::SolValue;
 use kzg::kzg_types::{ZFr, ZG1};
 use kzg_traits::{
     eip_4844::{
         blob_to_kzg_commitment_rust, blob_to_polynomial, compute_kzg_proof_rust,
-        evaluate_polynomial_in_evaluation_form, hash_to_bls_field, Blob,
+        evaluate_polynomial_in_evaluation_form, hash_to_bls_field, verify_kzg_proof_rust, Blob,
     },
-    Fr, G1,
+    FFTSettings as _, Fr, KZGSettings as _, G1,
 };
 use once_cell::sync::Lazy;
-use reth_primitives::B256;
+use reth_primitives::{Bytes, B256, U256};
 use sha2::{Digest as _, Sha256};
 
+use super::keccak::keccak;
+
 pub use kzg::{eip_4844::deserialize_blob_rust, kzg_proofs::KZGSettings};
 pub static KZG_SETTINGS_BIN: &[u8] = include_bytes!("../../kzg_settings/zkcrypto_kzg_settings.bin");
 
@@ -23,8 +26,149 @@ pub static KZG_SETTINGS: Lazy<KZGSettings> = Lazy::new(|| {
         .expect("failed to load trusted setup, please run `cargo run --bin gen_kzg_settings`")
 });
 
+/// Fingerprint of the compiled-in trusted setup, so operators can check which setup an
+/// image was built with.
+///
+/// This is the SHA-256 of [`KZG_SETTINGS_BIN`], the bincode serialized settings of the kzg
+/// backend, not of the G1/G2 points. It changes whenever the binary is regenerated with a
+/// different serialization, even for the same trusted setup.
+pub fn kzg_settings_fingerprint() -> B256 {
+    B256::new(Sha256::digest(KZG_SETTINGS_BIN).into())
+}
+
+/// Evaluation domain of the compiled-in trusted setup, for comparison with the constants
+/// hardcoded in verifier contracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KzgDomain {
+    /// Number of roots of unity in the domain.
+    pub width: usize,
+    /// Primitive root of unity generating the domain.
+    pub root_of_unity: KzgField,
+}
+
+pub fn kzg_domain() -> KzgDomain {
+    let fs = KZG_SETTINGS.get_fft_settings();
+    KzgDomain {
+        width: fs.get_max_width(),
+        root_of_unity: fs.get_expanded_roots_of_unity_at(1).to_bytes(),
+    }
+}
+
 pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
 
+// Blob sizing, every blob length check refers to these
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+pub const MAX_BLOBS_PER_BLOCK: usize = 6;
+
+pub const NUM_G1_POINTS: usize = FIELD_ELEMENTS_PER_BLOB;
+pub const NUM_G2_POINTS: usize = 65;
+pub const BYTES_PER_G1_POINT: usize = 48;
+pub const BYTES_PER_G2_POINT: usize = 96;
+
+pub type G1Points = [[u8; BYTES_PER_G1_POINT]; NUM_G1_POINTS];
+pub type G2Points = [[u8; BYTES_PER_G2_POINT]; NUM_G2_POINTS];
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum TrustedSetupError {
+    #[error("Missing {kind} point count at line {line}")]
+    MissingCount { kind: &'static str, line: usize },
+    #[error("Invalid {kind} point count at line {line}: `{value}`")]
+    InvalidCount {
+        kind: &'static str,
+        line: usize,
+        value: String,
+    },
+    #[error("Expected {expected} {kind} points, the setup declares {actual}")]
+    WrongPointCount {
+        kind: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Trusted setup ends after {found} of {expected} {kind} points")]
+    Truncated {
+        kind: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Invalid hex for {kind} point at line {line}")]
+    InvalidHex { kind: &'static str, line: usize },
+    #[error("Unexpected data after the last point at line {line}")]
+    TrailingData { line: usize },
+}
+
+/// Parses a trusted setup in the text format of the c-kzg `trusted_setup.txt`: the G1 and G2
+/// point counts on the first two lines, followed by one hex encoded point per line.
+///
+/// Unlike `parse_kzg_trusted_setup`, every error reports what failed and on which line.
+pub fn parse_and_validate_trusted_setup(
+    s: &str,
+) -> Result<(Box<G1Points>, Box<G2Points>), TrustedSetupError> {
+    // Line numbers are 1-based, like in an editor
+    let mut lines = s.lines().enumerate().map(|(i, line)| (i + 1, line.trim()));
+
+    let mut read_count =
+        |kind: &'static str, line: usize, expected: usize| -> Result<(), TrustedSetupError> {
+            let (_, value) = lines
+                .next()
+                .ok_or(TrustedSetupError::MissingCount { kind, line })?;
+            let actual = value
+                .parse::<usize>()
+                .map_err(|_| TrustedSetupError::InvalidCount {
+                    kind,
+                    line,
+                    value: value.to_string(),
+                })?;
+            if actual != expected {
+                return Err(TrustedSetupError::WrongPointCount {
+                    kind,
+                    expected,
+                    actual,
+                });
+            }
+            Ok(())
+        };
+    read_count("G1", 1, NUM_G1_POINTS)?;
+    read_count("G2", 2, NUM_G2_POINTS)?;
+
+    let g1 = read_points::<BYTES_PER_G1_POINT>(&mut lines, "G1", NUM_G1_POINTS)?;
+    let g2 = read_points::<BYTES_PER_G2_POINT>(&mut lines, "G2", NUM_G2_POINTS)?;
+
+    if let Some((line, _)) = lines.find(|(_, line)| !line.is_empty()) {
+        return Err(TrustedSetupError::TrailingData { line });
+    }
+
+    Ok((
+        g1.into_boxed_slice()
+            .try_into()
+            .expect("G1 point count checked"),
+        g2.into_boxed_slice()
+            .try_into()
+            .expect("G2 point count checked"),
+    ))
+}
+
+fn read_points<'a, const N: usize>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    kind: &'static str,
+    expected: usize,
+) -> Result<Vec<[u8; N]>, TrustedSetupError> {
+    let mut points = Vec::with_capacity(expected);
+    while points.len() < expected {
+        let (line, value) = lines.next().ok_or(TrustedSetupError::Truncated {
+            kind,
+            expected,
+            found: points.len(),
+        })?;
+        let mut point = [0u8; N];
+        hex::decode_to_slice(value, &mut point)
+            .map_err(|_| TrustedSetupError::InvalidHex { kind, line })?;
+        points.push(point);
+    }
+    Ok(points)
+}
+
 pub type KzgGroup = [u8; 48];
 pub type KzgField = [u8; 32];
 pub type KzgCommitment = KzgGroup;
@@ -39,11 +183,56 @@ pub enum Eip4844Error {
     ComputeKzgProof(String),
     #[error("Failed set commitment proof")]
     KzgDataPoison(String),
+    #[error("Blob versioned hash mismatch, expected: {expected}, got: {actual}")]
+    VersionHashMismatch { expected: B256, actual: B256 },
+    #[error("Blob commitment does not match the claimed commitment")]
+    CommitmentMismatch,
+    #[error("Blob data length {0} is not a non-zero multiple of the blob size")]
+    InvalidBlobLength(usize),
+    #[error("Blob data holds {count} blobs, more than the limit of {max}")]
+    TooManyBlobs { count: usize, max: usize },
+    #[error("Field element index {0} is out of range")]
+    InvalidFieldElementIndex(usize),
+    #[error("Tx list contains more than {max} transactions")]
+    TooManyTransactions { max: usize },
+    #[error("Failed to decode tx list at byte {offset}: {reason}")]
+    InvalidTransactionList { offset: usize, reason: String },
+    #[error("Duplicate transaction {0} in tx list")]
+    DuplicateTransaction(B256),
+    #[error("Unsupported transaction type {0:#04x}")]
+    UnsupportedTransactionType(u8),
+}
+
+/// Hash function used to derive the fiat-shamir challenge of the proof of equivalence.
+///
+/// The SP1 and RISC0 verifiers derive the challenge with Sha256, which is the default.
+/// Keccak256 is available for verifier variants that hash the blob with keccak instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChallengeHash {
+    #[default]
+    Sha256,
+    Keccak256,
 }
 
 pub fn get_evaluation_point(blob: &[u8], versioned_hash: &B256) -> ZFr {
-    let blob_hash = Sha256::digest(blob);
-    let x = Sha256::digest([blob_hash.to_vec(), versioned_hash.to_vec()].concat()).into();
+    get_evaluation_point_with_hash(blob, versioned_hash, ChallengeHash::default())
+}
+
+pub fn get_evaluation_point_with_hash(
+    blob: &[u8],
+    versioned_hash: &B256,
+    challenge_hash: ChallengeHash,
+) -> ZFr {
+    let x: [u8; 32] = match challenge_hash {
+        ChallengeHash::Sha256 => {
+            let blob_hash = Sha256::digest(blob);
+            Sha256::digest([blob_hash.to_vec(), versioned_hash.to_vec()].concat()).into()
+        }
+        ChallengeHash::Keccak256 => {
+            let blob_hash = keccak(blob);
+            keccak([blob_hash.to_vec(), versioned_hash.to_vec()].concat())
+        }
+    };
     hash_to_bls_field(&x)
 }
 
@@ -51,17 +240,33 @@ pub fn proof_of_equivalence(
     blob: &[u8],
     versioned_hash: &B256,
 ) -> Result<(KzgField, KzgField), Eip4844Error> {
+    proof_of_equivalence_with_hash(blob, versioned_hash, ChallengeHash::default())
+}
+
+pub fn proof_of_equivalence_with_hash(
+    blob: &[u8],
+    versioned_hash: &B256,
+    challenge_hash: ChallengeHash,
+) -> Result<(KzgField, KzgField), Eip4844Error> {
+    let x = get_evaluation_point_with_hash(blob, versioned_hash, challenge_hash).to_bytes();
+    let y = evaluate_blob_at(blob, &x)?;
+    Ok((x, y))
+}
+
+/// Evaluates the blob polynomial at an arbitrary point `z`.
+///
+/// This is the same evaluation the verifier contract performs at its challenge point, so
+/// integrators can plug in the contract's `z` and compare the result to `y`.
+pub fn evaluate_blob_at(blob: &[u8], z: &KzgField) -> Result<KzgField, Eip4844Error> {
     let blob_fields = Blob::from_bytes(blob)
         .and_then(|b| deserialize_blob_rust(&b))
         .map_err(|_| Eip4844Error::DeserializeBlob)?;
 
     let poly = blob_to_polynomial(&blob_fields).unwrap();
-    let x = get_evaluation_point(blob, versioned_hash);
-    let y = evaluate_polynomial_in_evaluation_form(&poly, &x, &KZG_SETTINGS.clone())
+    let z = ZFr::from_bytes(z).map_err(Eip4844Error::EvaluatePolynomial)?;
+    evaluate_polynomial_in_evaluation_form(&poly, &z, &KZG_SETTINGS.clone())
         .map(|fr| fr.to_bytes())
-        .map_err(|e| Eip4844Error::EvaluatePolynomial(e.to_string()))?;
-
-    Ok((x.to_bytes(), y))
+        .map_err(|e| Eip4844Error::EvaluatePolynomial(e.to_string()))
 }
 
 pub fn calc_kzg_proof(blob: &[u8], versioned_hash: &B256) -> Result<ZG1, Eip4844Error> {
@@ -77,6 +282,28 @@ pub fn calc_kzg_proof_with_point(blob: &[u8], z: ZFr) -> Result<ZG1, Eip4844Erro
     Ok(proof)
 }
 
+/// Computes the evaluation and KZG proof for the field element at `index` of the blob, for
+/// verifiers sampling single field elements instead of checking the whole blob.
+///
+/// Returns the evaluation point, i.e. the root of unity of the element, the evaluation
+/// (which equals the field element) and the proof.
+pub fn compute_point_proof(
+    blob: &[u8],
+    index: usize,
+) -> Result<(KzgField, KzgField, KzgGroup), Eip4844Error> {
+    if index >= FIELD_ELEMENTS_PER_BLOB {
+        return Err(Eip4844Error::InvalidFieldElementIndex(index));
+    }
+    let blob_fields = Blob::from_bytes(blob)
+        .and_then(|b| deserialize_blob_rust(&b))
+        .map_err(|_| Eip4844Error::DeserializeBlob)?;
+    // Blob elements are the evaluations over the bit-reversed roots of unity
+    let z = KZG_SETTINGS.get_fft_settings().get_roots_of_unity_at(index);
+    let (proof, y) = compute_kzg_proof_rust(&blob_fields, &z, &KZG_SETTINGS.clone())
+        .map_err(Eip4844Error::ComputeKzgProof)?;
+    Ok((z.to_bytes(), y.to_bytes(), proof.to_bytes()))
+}
+
 pub fn calc_kzg_proof_commitment(blob: &[u8]) -> Result<KzgGroup, Eip4844Error> {
     let blob_fields = Blob::from_bytes(blob)
         .and_then(|b| deserialize_blob_rust(&b))
@@ -88,25 +315,179 @@ pub fn calc_kzg_proof_commitment(blob: &[u8]) -> Result<KzgGroup, Eip4844Error>
     )
 }
 
+/// Verifies that `proof` opens `commitment` at `z` to the value `y`.
+pub fn verify_kzg_proof(
+    commitment: &KzgCommitment,
+    z: &KzgField,
+    y: &KzgField,
+    proof: &KzgGroup,
+) -> Result<bool, Eip4844Error> {
+    verify_kzg_proof_rust(
+        &ZG1::from_bytes(commitment).map_err(Eip4844Error::KzgDataPoison)?,
+        &ZFr::from_bytes(z).map_err(Eip4844Error::KzgDataPoison)?,
+        &ZFr::from_bytes(y).map_err(Eip4844Error::KzgDataPoison)?,
+        &ZG1::from_bytes(proof).map_err(Eip4844Error::KzgDataPoison)?,
+        &KZG_SETTINGS.clone(),
+    )
+    .map_err(Eip4844Error::ComputeKzgProof)
+}
+
+/// Like [`verify_kzg_proof`], but first recomputes the commitment from the blob and checks
+/// it against the claimed `commitment`.
+pub fn verify_blob_kzg_proof(
+    blob: &[u8],
+    commitment: &KzgCommitment,
+    z: &KzgField,
+    y: &KzgField,
+    proof: &KzgGroup,
+) -> Result<bool, Eip4844Error> {
+    if calc_kzg_proof_commitment(blob)? != *commitment {
+        return Err(Eip4844Error::CommitmentMismatch);
+    }
+    verify_kzg_proof(commitment, z, y, proof)
+}
+
+/// Verifies the proof of equivalence against a commitment already known to the caller, e.g.
+/// from the block header, without recomputing it from the blob.
+///
+/// The challenge is derived from the blob and the commitment's versioned hash as in
+/// [`proof_of_equivalence`]. A wrong commitment makes the proof fail to verify.
+pub fn verify_proof_of_equivalence(
+    blob: &[u8],
+    commitment: &KzgCommitment,
+    proof: &KzgGroup,
+) -> Result<bool, Eip4844Error> {
+    let (x, y) = proof_of_equivalence(blob, &commitment_to_version_hash(commitment))?;
+    verify_kzg_proof(commitment, &x, &y, proof)
+}
+
 pub fn commitment_to_version_hash(commitment: &[u8; 48]) -> B256 {
+    commitment_to_version_hash_with_version(commitment, VERSIONED_HASH_VERSION_KZG)
+}
+
+/// Same as [`commitment_to_version_hash`] but with a custom version byte, for schemes that
+/// tag their versioned hashes differently from EIP-4844 KZG commitments.
+pub fn commitment_to_version_hash_with_version(commitment: &[u8; 48], version: u8) -> B256 {
     let mut hash = Sha256::digest(commitment);
-    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash[0] = version;
     B256::new(hash.into())
 }
 
+/// Checks that the blob commits to the `expected` versioned hash, e.g. an entry of a
+/// header's blob versioned hashes.
+pub fn verify_blob_against_version_hash(blob: &[u8], expected: &B256) -> Result<(), Eip4844Error> {
+    let actual = commitment_to_version_hash(&calc_kzg_proof_commitment(blob)?);
+    if actual != *expected {
+        return Err(Eip4844Error::VersionHashMismatch {
+            expected: *expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
 pub fn kzg_proof_to_bytes(proof: &ZG1) -> KzgGroup {
     proof.to_bytes()
 }
 
+/// Converts a field element to the `U256` committed to in the public input.
+///
+/// The bytes are interpreted as little-endian, the same way the protocol instance encodes
+/// the proof of equivalence. This is only meant for the public input: the point evaluation
+/// precompile reads field elements big-endian, see [`abi_encode_equivalence`].
+pub fn kzg_field_to_u256(field: &KzgField) -> U256 {
+    U256::from_le_bytes(*field)
+}
+
+/// Inverse of [`kzg_field_to_u256`].
+pub fn u256_to_kzg_field(value: &U256) -> KzgField {
+    value.to_le_bytes()
+}
+
+/// ABI encodes the proof of equivalence outputs as
+/// `(bytes32 versionedHash, uint256 x, uint256 y, bytes commitment, bytes proof)`.
+///
+/// `x` and `y` are encoded big-endian, the byte order the point evaluation precompile reads
+/// them in, so the words can be passed on to it unchanged.
+pub fn abi_encode_equivalence(
+    versioned_hash: &B256,
+    x: &KzgField,
+    y: &KzgField,
+    commitment: &KzgCommitment,
+    proof: &KzgGroup,
+) -> Vec<u8> {
+    (
+        *versioned_hash,
+        U256::from_be_bytes(*x),
+        U256::from_be_bytes(*y),
+        Bytes::copy_from_slice(commitment),
+        Bytes::copy_from_slice(proof),
+    )
+        .abi_encode_params()
+}
+
+/// Splits concatenated blob data into its blobs, rejecting data that is empty, not an
+/// exact multiple of [`BYTES_PER_BLOB`] or holds more than [`MAX_BLOBS_PER_BLOCK`] blobs.
+pub fn split_blobs(data: &[u8]) -> Result<Vec<&[u8]>, Eip4844Error> {
+    split_blobs_with_limit(data, MAX_BLOBS_PER_BLOCK)
+}
+
+/// Same as [`split_blobs`] with a custom limit on the number of blobs.
+pub fn split_blobs_with_limit(data: &[u8], max_blobs: usize) -> Result<Vec<&[u8]>, Eip4844Error> {
+    if data.is_empty() || data.len() % BYTES_PER_BLOB != 0 {
+        return Err(Eip4844Error::InvalidBlobLength(data.len()));
+    }
+    let count = data.len() / BYTES_PER_BLOB;
+    if count > max_blobs {
+        return Err(Eip4844Error::TooManyBlobs {
+            count,
+            max: max_blobs,
+        });
+    }
+    Ok(data.chunks_exact(BYTES_PER_BLOB).collect())
+}
+
+/// Computes the proof of equivalence for a single blob without building a block.
+///
+/// Returns the evaluation `y` at the fiat-shamir point derived from the blob and its
+/// commitment, together with the commitment and the KZG opening proof for that point.
+pub fn prove_blob(blob: &[u8]) -> Result<(KzgField, KzgCommitment, KzgGroup), Eip4844Error> {
+    let commitment = calc_kzg_proof_commitment(blob)?;
+    let versioned_hash = commitment_to_version_hash(&commitment);
+    let (_, y) = proof_of_equivalence(blob, &versioned_hash)?;
+    let proof = calc_kzg_proof(blob, &versioned_hash)?;
+    Ok((y, commitment, kzg_proof_to_bytes(&proof)))
+}
+
+/// Runs [`prove_blob`] for each blob, returning every blob's versioned hash together with
+/// its evaluation, commitment and proof.
+pub fn prove_blobs<B: AsRef<[u8]>>(
+    blobs: &[B],
+) -> Result<Vec<(B256, KzgField, KzgCommitment, KzgGroup)>, Eip4844Error> {
+    blobs
+        .iter()
+        .map(|blob| {
+            let (y, commitment, proof) = prove_blob(blob.as_ref())?;
+            Ok((
+                commitment_to_version_hash(&commitment),
+                y,
+                commitment,
+                proof,
+            ))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use kzg_traits::{
-        eip_4844::{verify_kzg_proof_rust, BYTES_PER_FIELD_ELEMENT},
-        G1,
-    };
+    use kzg_traits::{FFTSettings as _, KZGSettings as _, G1};
     use reth_primitives::revm_primitives::kzg::{G1Points, G2Points, G1_POINTS, G2_POINTS};
-    use reth_primitives::revm_primitives::Bytes;
+
+    /// A valid blob: every byte is below 64, so each field element is canonical.
+    fn blob_fixture() -> Vec<u8> {
+        (0..BYTES_PER_BLOB).map(|v| (v % 64) as u8).collect()
+    }
 
     pub fn verify_kzg_proof_evm(
         commitment: &KzgCommitment,
@@ -146,7 +527,7 @@ mod test {
 
     #[test]
     fn test_blob_to_kzg_commitment() {
-        let blob = Blob::from_bytes(&[0u8; 131072]).unwrap();
+        let blob = Blob::from_bytes(&[0u8; BYTES_PER_BLOB]).unwrap();
         let commitment = blob_to_kzg_commitment_rust(
             &deserialize_blob_rust(&blob).unwrap(),
             &KZG_SETTINGS.clone(),
@@ -162,7 +543,7 @@ mod test {
     #[test]
     fn test_verify_kzg_proof() {
         let kzg_settings = KZG_SETTINGS.clone();
-        let data = (0u64..131072).map(|v| (v % 64) as u8).collect::<Vec<u8>>();
+        let data = blob_fixture();
         let blob = Blob::from_bytes(&data).unwrap();
         let blob_fields = deserialize_blob_rust(&blob).unwrap();
         let commitment = calc_kzg_proof_commitment(&blob.bytes).unwrap();
@@ -185,7 +566,7 @@ mod test {
 
     #[test]
     fn test_verify_kzg_proof_in_precompile() {
-        let data = (0u64..131072).map(|v| (v % 64) as u8).collect::<Vec<u8>>();
+        let data = blob_fixture();
         let blob = Blob::from_bytes(&data).unwrap();
         let blob_fields = deserialize_blob_rust(&blob).unwrap();
         let commitment = calc_kzg_proof_commitment(&blob.bytes).unwrap();
@@ -212,4 +593,351 @@ mod test {
             assert!(!verify_kzg_proof_evm(&commitment, &x, &y, &proof,).unwrap());
         }
     }
+
+    #[test]
+    fn test_prove_blob() {
+        let data = blob_fixture();
+        let (y, commitment, proof) = prove_blob(&data).unwrap();
+
+        assert_eq!(commitment, calc_kzg_proof_commitment(&data).unwrap());
+        let versioned_hash = commitment_to_version_hash(&commitment);
+        let (x, expected_y) = proof_of_equivalence(&data, &versioned_hash).unwrap();
+        assert_eq!(y, expected_y);
+
+        assert!(verify_kzg_proof_evm(
+            &commitment,
+            &ZFr::from_bytes(&x).unwrap(),
+            &ZFr::from_bytes(&y).unwrap(),
+            &ZG1::from_bytes(&proof).unwrap(),
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn test_evaluate_blob_at() {
+        let data = blob_fixture();
+        let commitment = calc_kzg_proof_commitment(&data).unwrap();
+        let versioned_hash = commitment_to_version_hash(&commitment);
+        let (x, y) = proof_of_equivalence(&data, &versioned_hash).unwrap();
+        assert_eq!(evaluate_blob_at(&data, &x).unwrap(), y);
+
+        let z = hash_to_bls_field(&[5; BYTES_PER_FIELD_ELEMENT]).to_bytes();
+        assert_ne!(evaluate_blob_at(&data, &z).unwrap(), y);
+    }
+
+    #[test]
+    fn test_commitment_to_version_hash_with_version() {
+        let commitment = calc_kzg_proof_commitment(&[0u8; BYTES_PER_BLOB]).unwrap();
+        let default_hash = commitment_to_version_hash(&commitment);
+        assert_eq!(
+            commitment_to_version_hash_with_version(&commitment, VERSIONED_HASH_VERSION_KZG),
+            default_hash
+        );
+
+        let other_hash = commitment_to_version_hash_with_version(&commitment, 0x02);
+        assert_eq!(other_hash[0], 0x02);
+        assert_eq!(other_hash[1..], default_hash[1..]);
+    }
+
+    #[test]
+    fn test_kzg_field_u256_roundtrip() {
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        assert_eq!(kzg_field_to_u256(&one), U256::from(1));
+
+        let data = blob_fixture();
+        let commitment = calc_kzg_proof_commitment(&data).unwrap();
+        let (x, y) = proof_of_equivalence(&data, &commitment_to_version_hash(&commitment)).unwrap();
+        assert_eq!(u256_to_kzg_field(&kzg_field_to_u256(&x)), x);
+        assert_eq!(u256_to_kzg_field(&kzg_field_to_u256(&y)), y);
+    }
+
+    #[test]
+    fn test_kzg_settings_fingerprint() {
+        // Update when regenerating `zkcrypto_kzg_settings.bin`
+        assert_eq!(
+            kzg_settings_fingerprint(),
+            alloy_primitives::b256!(
+                "1b4de2ed5ebaae9ff855851ece64012dc5b334f8a128f713f5dec8f88b472359"
+            )
+        );
+    }
+
+    #[test]
+    fn test_prove_blobs() {
+        let blobs = [
+            blob_fixture(),
+            (0..BYTES_PER_BLOB)
+                .map(|v| (v % 32) as u8)
+                .collect::<Vec<u8>>(),
+        ];
+        let proofs = prove_blobs(&blobs).unwrap();
+        assert_eq!(proofs.len(), 2);
+        assert_ne!(proofs[0].0, proofs[1].0);
+        assert_ne!(proofs[0].3, proofs[1].3);
+
+        for (blob, (versioned_hash, y, commitment, proof)) in blobs.iter().zip(proofs) {
+            assert_eq!((y, commitment, proof), prove_blob(blob).unwrap());
+            assert_eq!(versioned_hash, commitment_to_version_hash(&commitment));
+        }
+    }
+
+    #[test]
+    fn test_proof_of_equivalence_challenge_hash() {
+        let data = blob_fixture();
+        let versioned_hash = commitment_to_version_hash(&calc_kzg_proof_commitment(&data).unwrap());
+
+        let sha =
+            proof_of_equivalence_with_hash(&data, &versioned_hash, ChallengeHash::Sha256).unwrap();
+        let keccak =
+            proof_of_equivalence_with_hash(&data, &versioned_hash, ChallengeHash::Keccak256)
+                .unwrap();
+        assert_eq!(sha, proof_of_equivalence(&data, &versioned_hash).unwrap());
+        assert_ne!(sha.0, keccak.0);
+        assert_eq!(evaluate_blob_at(&data, &keccak.0).unwrap(), keccak.1);
+    }
+
+    #[test]
+    fn test_abi_encode_equivalence() {
+        let versioned_hash = B256::repeat_byte(0x01);
+        let mut x = [0u8; 32];
+        x[0] = 2;
+        let mut y = [0u8; 32];
+        y[0] = 3;
+        let commitment = [4u8; 48];
+        let proof = [5u8; 48];
+
+        let encoded = abi_encode_equivalence(&versioned_hash, &x, &y, &commitment, &proof);
+
+        let word = |i: usize| &encoded[i * 32..(i + 1) * 32];
+        let uint = |v: u64| U256::from(v).to_be_bytes::<32>();
+        assert_eq!(encoded.len(), 11 * 32);
+        assert_eq!(word(0), versioned_hash.as_slice());
+        assert_eq!(word(1), x);
+        assert_eq!(word(2), y);
+        // Offsets of the dynamic `bytes` values
+        assert_eq!(word(3), uint(5 * 32));
+        assert_eq!(word(4), uint(8 * 32));
+        assert_eq!(word(5), uint(48));
+        assert_eq!(&encoded[6 * 32..6 * 32 + 48], commitment);
+        assert_eq!(&encoded[6 * 32 + 48..8 * 32], [0u8; 16]);
+        assert_eq!(word(8), uint(48));
+        assert_eq!(&encoded[9 * 32..9 * 32 + 48], proof);
+        assert_eq!(&encoded[9 * 32 + 48..], [0u8; 16]);
+    }
+
+    #[test]
+    fn test_abi_encode_equivalence_in_precompile() {
+        let data = blob_fixture();
+        let (y, commitment, proof) = prove_blob(&data).unwrap();
+        let versioned_hash = commitment_to_version_hash(&commitment);
+        let (x, _) = proof_of_equivalence(&data, &versioned_hash).unwrap();
+
+        // | versioned_hash |  z  |  y  | commitment | proof |
+        let encoded = abi_encode_equivalence(&versioned_hash, &x, &y, &commitment, &proof);
+        let mut input = encoded[..3 * 32].to_vec();
+        input.extend_from_slice(&encoded[6 * 32..6 * 32 + 48]);
+        input.extend_from_slice(&encoded[9 * 32..9 * 32 + 48]);
+
+        assert!(reth_primitives::revm_precompile::kzg_point_evaluation::run(
+            &Bytes::from(input),
+            u64::MAX,
+            &reth_primitives::revm_primitives::env::Env::default(),
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_verify_kzg_proof_bytes() {
+        let data = blob_fixture();
+        let (y, commitment, proof) = prove_blob(&data).unwrap();
+        let (x, _) = proof_of_equivalence(&data, &commitment_to_version_hash(&commitment)).unwrap();
+        assert!(verify_kzg_proof(&commitment, &x, &y, &proof).unwrap());
+
+        let other_y = evaluate_blob_at(&data, &[0u8; 32]).unwrap();
+        assert!(!verify_kzg_proof(&commitment, &x, &other_y, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_verify_blob_kzg_proof() {
+        let data = blob_fixture();
+        let (y, commitment, proof) = prove_blob(&data).unwrap();
+        let (x, _) = proof_of_equivalence(&data, &commitment_to_version_hash(&commitment)).unwrap();
+        assert!(verify_blob_kzg_proof(&data, &commitment, &x, &y, &proof).unwrap());
+
+        let other_commitment = calc_kzg_proof_commitment(&[0u8; BYTES_PER_BLOB]).unwrap();
+        assert!(matches!(
+            verify_blob_kzg_proof(&data, &other_commitment, &x, &y, &proof),
+            Err(Eip4844Error::CommitmentMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_verify_blob_against_version_hash() {
+        let data = blob_fixture();
+        let versioned_hash = commitment_to_version_hash(&calc_kzg_proof_commitment(&data).unwrap());
+        assert!(verify_blob_against_version_hash(&data, &versioned_hash).is_ok());
+
+        assert!(matches!(
+            verify_blob_against_version_hash(&[0u8; BYTES_PER_BLOB], &versioned_hash),
+            Err(Eip4844Error::VersionHashMismatch { expected, .. }) if expected == versioned_hash
+        ));
+    }
+
+    fn trusted_setup_text() -> String {
+        let mut lines = vec![NUM_G1_POINTS.to_string(), NUM_G2_POINTS.to_string()];
+        lines.extend(G1Points::as_ref(G1_POINTS).iter().map(hex::encode));
+        lines.extend(G2Points::as_ref(G2_POINTS).iter().map(hex::encode));
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_parse_and_validate_trusted_setup() {
+        let (g1, g2) = parse_and_validate_trusted_setup(&trusted_setup_text()).unwrap();
+        assert_eq!(g1.as_slice(), G1Points::as_ref(G1_POINTS));
+        assert_eq!(g2.as_slice(), G2Points::as_ref(G2_POINTS));
+    }
+
+    #[test]
+    fn test_parse_trusted_setup_truncated() {
+        let text = trusted_setup_text();
+        let truncated = text.lines().take(2 + 100).collect::<Vec<_>>().join("\n");
+        assert_eq!(
+            parse_and_validate_trusted_setup(&truncated),
+            Err(TrustedSetupError::Truncated {
+                kind: "G1",
+                expected: NUM_G1_POINTS,
+                found: 100,
+            })
+        );
+
+        let wrong_count = text.replacen("4096", "4095", 1);
+        assert_eq!(
+            parse_and_validate_trusted_setup(&wrong_count),
+            Err(TrustedSetupError::WrongPointCount {
+                kind: "G1",
+                expected: NUM_G1_POINTS,
+                actual: 4095,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_trusted_setup_malformed_hex() {
+        let mut lines = trusted_setup_text()
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        lines[2 + NUM_G1_POINTS + 3].replace_range(..2, "zz");
+        assert_eq!(
+            parse_and_validate_trusted_setup(&lines.join("\n")),
+            Err(TrustedSetupError::InvalidHex {
+                kind: "G2",
+                line: 2 + NUM_G1_POINTS + 4,
+            })
+        );
+    }
+
+    #[test]
+    fn test_blob_size_constants() {
+        assert_eq!(
+            BYTES_PER_BLOB,
+            FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT
+        );
+        assert_eq!(BYTES_PER_BLOB, kzg_traits::eip_4844::BYTES_PER_BLOB);
+        assert_eq!(
+            FIELD_ELEMENTS_PER_BLOB,
+            kzg_traits::eip_4844::FIELD_ELEMENTS_PER_BLOB
+        );
+        assert_eq!(
+            BYTES_PER_FIELD_ELEMENT,
+            kzg_traits::eip_4844::BYTES_PER_FIELD_ELEMENT
+        );
+    }
+
+    #[test]
+    fn test_split_blobs() {
+        let mut data = vec![0u8; BYTES_PER_BLOB];
+        data.extend(vec![1u8; BYTES_PER_BLOB]);
+        let blobs = split_blobs(&data).unwrap();
+        assert_eq!(blobs.len(), 2);
+        assert!(blobs[1].iter().all(|b| *b == 1));
+
+        assert!(matches!(
+            split_blobs(&data[..BYTES_PER_BLOB + 1]),
+            Err(Eip4844Error::InvalidBlobLength(len)) if len == BYTES_PER_BLOB + 1
+        ));
+        assert!(matches!(
+            split_blobs(&[]),
+            Err(Eip4844Error::InvalidBlobLength(0))
+        ));
+    }
+
+    #[test]
+    fn test_kzg_domain() {
+        let domain = kzg_domain();
+        assert_eq!(domain.width, FIELD_ELEMENTS_PER_BLOB);
+
+        // The root has order exactly `width`
+        let fs = KZG_SETTINGS.get_fft_settings();
+        assert!(fs.get_expanded_roots_of_unity_at(domain.width).is_one());
+        assert!(!fs.get_expanded_roots_of_unity_at(domain.width / 2).is_one());
+    }
+
+    #[test]
+    fn test_split_blobs_limit() {
+        let data = vec![0u8; BYTES_PER_BLOB * (MAX_BLOBS_PER_BLOCK + 1)];
+        assert_eq!(
+            split_blobs(&data[..BYTES_PER_BLOB * MAX_BLOBS_PER_BLOCK])
+                .unwrap()
+                .len(),
+            MAX_BLOBS_PER_BLOCK
+        );
+        assert!(matches!(
+            split_blobs(&data),
+            Err(Eip4844Error::TooManyBlobs { count, max: MAX_BLOBS_PER_BLOCK })
+                if count == MAX_BLOBS_PER_BLOCK + 1
+        ));
+        assert!(matches!(
+            split_blobs_with_limit(&data[..2 * BYTES_PER_BLOB], 1),
+            Err(Eip4844Error::TooManyBlobs { count: 2, max: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_compute_point_proof() {
+        let data = blob_fixture();
+        let commitment = calc_kzg_proof_commitment(&data).unwrap();
+
+        let index = 1234;
+        let (z, y, proof) = compute_point_proof(&data, index).unwrap();
+        let element = &data[index * BYTES_PER_FIELD_ELEMENT..(index + 1) * BYTES_PER_FIELD_ELEMENT];
+        assert_eq!(y, element);
+        assert!(verify_kzg_proof(&commitment, &z, &y, &proof).unwrap());
+
+        let (other_z, _, _) = compute_point_proof(&data, index + 1).unwrap();
+        assert!(!verify_kzg_proof(&commitment, &other_z, &y, &proof).unwrap());
+
+        assert!(matches!(
+            compute_point_proof(&data, FIELD_ELEMENTS_PER_BLOB),
+            Err(Eip4844Error::InvalidFieldElementIndex(
+                FIELD_ELEMENTS_PER_BLOB
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_verify_proof_of_equivalence() {
+        let data = blob_fixture();
+        let (y, commitment, proof) = prove_blob(&data).unwrap();
+        let (x, _) = proof_of_equivalence(&data, &commitment_to_version_hash(&commitment)).unwrap();
+
+        assert!(verify_proof_of_equivalence(&data, &commitment, &proof).unwrap());
+        assert!(verify_blob_kzg_proof(&data, &commitment, &x, &y, &proof).unwrap());
+
+        // Without recomputing, a wrong commitment shows up as a failed proof
+        let other_commitment = calc_kzg_proof_commitment(&[0u8; BYTES_PER_BLOB]).unwrap();
+        assert!(!verify_proof_of_equivalence(&data, &other_commitment, &proof).unwrap());
+        assert!(verify_blob_kzg_proof(&data, &other_commitment, &x, &y, &proof).is_err());
+    }
 }
@@ -6,6 +6,7 @@ use reth_primitives::{Header, U256};
 #[cfg(not(feature = "std"))]
 use crate::no_std::*;
 use crate::{
+    builder::try_calculate_block_header,
     consts::{SupportedChainSpecs, VerifierType},
     input::{BlobProofType, BlockMetadata, EthDeposit, GuestInput, Transition},
     primitives::{
@@ -29,6 +30,7 @@ pub struct ProtocolInstance {
 
 impl ProtocolInstance {
     pub fn new(input: &GuestInput, header: &Header, proof_type: VerifierType) -> Result<Self> {
+        input.taiko.validate_blob_data()?;
         let blob_used = input.taiko.block_proposed.meta.blobUsed;
         // If blob is used, tx_list_hash is the commitment to the blob
         // and we need to verify the blob hash matches the blob data.
@@ -49,8 +51,10 @@ impl ProtocolInstance {
                     let points =
                         eip4844::proof_of_equivalence(&input.taiko.tx_data, &versioned_hash)?;
                     ct.end();
-                    proof_of_equivalence =
-                        (U256::from_le_bytes(points.0), U256::from_le_bytes(points.1));
+                    proof_of_equivalence = (
+                        eip4844::kzg_field_to_u256(&points.0),
+                        eip4844::kzg_field_to_u256(&points.1),
+                    );
                 }
                 crate::input::BlobProofType::KzgVersionedHash => {
                     let ct = CycleTracker::start("proof_of_commitment");
@@ -168,9 +172,14 @@ impl ProtocolInstance {
 
     // keccak256(abi.encode(tran, newInstance, prover, metaHash))
     pub fn instance_hash(&self) -> B256 {
+        keccak(self.abi_encoded()).into()
+    }
+
+    /// The ABI encoded public input that [`ProtocolInstance::instance_hash`] hashes.
+    pub fn abi_encoded(&self) -> Vec<u8> {
         // packages/protocol/contracts/verifiers/libs/LibPublicInput.sol
         // "VERIFY_PROOF", _chainId, _verifierContract, _tran, _newInstance, _prover, _metaHash
-        let data = (
+        (
             "VERIFY_PROOF",
             self.chain_id,
             self.verifier_address,
@@ -185,9 +194,164 @@ impl ProtocolInstance {
             .iter()
             .skip(32)
             .copied()
-            .collect::<Vec<u8>>();
-        keccak(data).into()
+            .collect::<Vec<u8>>()
+    }
+}
+
+/// Builds the block from the input and returns the resulting public input hash.
+///
+/// This is the computation the guests perform before committing to the public input, so
+/// auditors can recompute the hash independently of any prover. SGX additionally binds its
+/// instance address through [`ProtocolInstance::sgx_instance`], which is not known here.
+/// Inputs that fail to build return an error.
+pub fn compute_pi_hash(input: &GuestInput, proof_type: VerifierType) -> Result<B256> {
+    let header = try_calculate_block_header(input)?;
+    Ok(ProtocolInstance::new(input, &header, proof_type)?.instance_hash())
+}
+
+/// Checks that `abi_encoded_pi` is the public input the verifier contract reconstructs for
+/// this block, i.e. that it hashes to the instance hash the provers commit to. As with
+/// [`compute_pi_hash`], the SGX instance address is left unset.
+pub fn verify_pi_abi_consistency(
+    input: &GuestInput,
+    header: &Header,
+    abi_encoded_pi: &[u8],
+    verifier: VerifierType,
+) -> Result<()> {
+    let expected = ProtocolInstance::new(input, header, verifier)?.instance_hash();
+    let actual: B256 = keccak(abi_encoded_pi).into();
+    ensure!(
+        actual == expected,
+        "public input hash mismatch, expected: {expected}, got: {actual}"
+    );
+    Ok(())
+}
+
+/// Public input hashes of the same block under two verifier configurations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PiHashDelta {
+    pub from: B256,
+    pub to: B256,
+}
+
+impl PiHashDelta {
+    /// Whether switching configurations changes the public input hash.
+    pub fn differs(&self) -> bool {
+        self.from != self.to
+    }
+}
+
+/// Computes the public input hash of the block under both verifier types, so migrations can
+/// check whether a protocol change affects the hashes of existing blocks.
+pub fn pi_hash_delta(
+    input: &GuestInput,
+    header: &Header,
+    from: VerifierType,
+    to: VerifierType,
+) -> Result<PiHashDelta> {
+    Ok(PiHashDelta {
+        from: ProtocolInstance::new(input, header, from)?.instance_hash(),
+        to: ProtocolInstance::new(input, header, to)?.instance_hash(),
+    })
+}
+
+/// Computes the root of the merkle tree over the public input hashes of a batch.
+///
+/// Leaves are `keccak256(0x00 || pi_hash)` and parents `keccak256(0x01 || left || right)`, so a
+/// parent can never be passed off as a leaf. The leaves are padded with zero hashes to the
+/// next power of two. An empty batch has a zero root.
+pub fn pi_hash_merkle_root(hashes: &[B256]) -> B256 {
+    merkle_levels(hashes)
+        .last()
+        .map(|root| root[0])
+        .unwrap_or_default()
+}
+
+/// Returns the sibling hashes from the leaf at `index` up to the root, or `None` if the
+/// index is out of range.
+pub fn pi_hash_merkle_proof(hashes: &[B256], index: usize) -> Option<Vec<B256>> {
+    if index >= hashes.len() {
+        return None;
+    }
+    let levels = merkle_levels(hashes);
+    Some(
+        levels[..levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(depth, level)| level[(index >> depth) ^ 1])
+            .collect(),
+    )
+}
+
+/// Checks that `leaf` is the public input hash at `index` of a batch of `leaf_count` hashes
+/// with merkle root `root`, given the sibling hashes returned by [`pi_hash_merkle_proof`].
+///
+/// Padding positions and proofs of the wrong depth for the batch size are rejected.
+pub fn verify_pi_hash_inclusion(
+    root: &B256,
+    leaf: &B256,
+    proof: &[B256],
+    index: usize,
+    leaf_count: usize,
+) -> bool {
+    if index >= leaf_count {
+        return false;
+    }
+    let Some(depth) = merkle_depth(leaf_count) else {
+        return false;
+    };
+    if proof.len() != depth {
+        return false;
+    }
+    let computed = proof
+        .iter()
+        .enumerate()
+        .fold(merkle_leaf(leaf), |node, (depth, sibling)| {
+            if (index >> depth) & 1 == 0 {
+                merkle_parent(&node, sibling)
+            } else {
+                merkle_parent(sibling, &node)
+            }
+        });
+    computed == *root
+}
+
+/// Number of levels above the leaves in a tree over `leaf_count` leaves.
+fn merkle_depth(leaf_count: usize) -> Option<usize> {
+    leaf_count
+        .checked_next_power_of_two()
+        .map(|width| width.trailing_zeros() as usize)
+}
+
+fn merkle_levels(hashes: &[B256]) -> Vec<Vec<B256>> {
+    if hashes.is_empty() {
+        return Vec::new();
+    }
+    let mut leaves = hashes.iter().map(merkle_leaf).collect::<Vec<_>>();
+    leaves.resize(hashes.len().next_power_of_two(), B256::ZERO);
+
+    let mut levels = vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let parents = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| merkle_parent(&pair[0], &pair[1]))
+            .collect();
+        levels.push(parents);
     }
+    levels
+}
+
+const MERKLE_LEAF_PREFIX: u8 = 0x00;
+const MERKLE_NODE_PREFIX: u8 = 0x01;
+
+fn merkle_leaf(hash: &B256) -> B256 {
+    keccak([&[MERKLE_LEAF_PREFIX], hash.as_slice()].concat()).into()
+}
+
+fn merkle_parent(left: &B256, right: &B256) -> B256 {
+    keccak([&[MERKLE_NODE_PREFIX], left.as_slice(), right.as_slice()].concat()).into()
 }
 
 // Make sure the verifier supports the blob proof type
@@ -306,4 +470,174 @@ mod tests {
             "8b0e2833f7bae47f6886e5f172d90b12e330485bfe366d8ed4d53b2114d47e68"
         );
     }
+
+    #[test]
+    fn test_pi_hash_delta() {
+        let mut input = GuestInput::default();
+        let header = Header::default();
+
+        let delta = pi_hash_delta(&input, &header, VerifierType::SP1, VerifierType::RISC0).unwrap();
+        assert!(!delta.differs());
+
+        input.chain_spec.verifier_address = [
+            (VerifierType::SP1, Some(Address::repeat_byte(1))),
+            (VerifierType::RISC0, Some(Address::repeat_byte(2))),
+        ]
+        .into();
+        let delta = pi_hash_delta(&input, &header, VerifierType::SP1, VerifierType::RISC0).unwrap();
+        assert!(delta.differs());
+        assert_eq!(
+            delta.from,
+            ProtocolInstance::new(&input, &header, VerifierType::SP1)
+                .unwrap()
+                .instance_hash()
+        );
+    }
+
+    #[test]
+    fn test_pi_hash_merkle_root() {
+        let hashes = (1u8..=3).map(B256::repeat_byte).collect::<Vec<_>>();
+        let root = pi_hash_merkle_root(&hashes);
+        assert_eq!(root, pi_hash_merkle_root(&hashes.clone()));
+        assert_eq!(
+            root,
+            merkle_parent(
+                &merkle_parent(&merkle_leaf(&hashes[0]), &merkle_leaf(&hashes[1])),
+                &merkle_parent(&merkle_leaf(&hashes[2]), &B256::ZERO)
+            )
+        );
+
+        let mut swapped = hashes.clone();
+        swapped.swap(0, 1);
+        assert_ne!(root, pi_hash_merkle_root(&swapped));
+
+        assert_eq!(pi_hash_merkle_root(&hashes[..1]), merkle_leaf(&hashes[0]));
+        assert_eq!(pi_hash_merkle_root(&[]), B256::ZERO);
+    }
+
+    #[test]
+    fn test_pi_hash_merkle_proof() {
+        let hashes = (1u8..=5).map(B256::repeat_byte).collect::<Vec<_>>();
+        let root = pi_hash_merkle_root(&hashes);
+        for (index, leaf) in hashes.iter().enumerate() {
+            let proof = pi_hash_merkle_proof(&hashes, index).unwrap();
+            assert_eq!(proof.len(), 3);
+            assert!(verify_pi_hash_inclusion(
+                &root,
+                leaf,
+                &proof,
+                index,
+                hashes.len()
+            ));
+        }
+        assert!(pi_hash_merkle_proof(&hashes, hashes.len()).is_none());
+
+        let proof = pi_hash_merkle_proof(&hashes[..1], 0).unwrap();
+        assert!(proof.is_empty());
+        assert!(verify_pi_hash_inclusion(
+            &pi_hash_merkle_root(&hashes[..1]),
+            &hashes[0],
+            &proof,
+            0,
+            1
+        ));
+    }
+
+    #[test]
+    fn test_verify_pi_hash_inclusion() {
+        let hashes = (1u8..=4).map(B256::repeat_byte).collect::<Vec<_>>();
+        let root = pi_hash_merkle_root(&hashes);
+        let mut proof = pi_hash_merkle_proof(&hashes, 2).unwrap();
+        assert!(verify_pi_hash_inclusion(&root, &hashes[2], &proof, 2, 4));
+
+        assert!(!verify_pi_hash_inclusion(&root, &hashes[2], &proof, 3, 4));
+        assert!(!verify_pi_hash_inclusion(&root, &hashes[2], &proof, 6, 4));
+        assert!(!verify_pi_hash_inclusion(&root, &hashes[1], &proof, 2, 4));
+
+        proof[0] = B256::repeat_byte(9);
+        assert!(!verify_pi_hash_inclusion(&root, &hashes[2], &proof, 2, 4));
+    }
+
+    #[test]
+    fn test_verify_pi_hash_inclusion_rejects_padding() {
+        let hashes = (1u8..=3).map(B256::repeat_byte).collect::<Vec<_>>();
+        let root = pi_hash_merkle_root(&hashes);
+
+        // The padding leaf at index 3 is part of the tree, but not of the batch
+        let levels = merkle_levels(&hashes);
+        let padding_proof = vec![levels[0][2], levels[1][0]];
+        assert!(!verify_pi_hash_inclusion(
+            &root,
+            &B256::ZERO,
+            &padding_proof,
+            3,
+            3
+        ));
+
+        // An inner node does not verify as a leaf with a shorter proof
+        let proof = pi_hash_merkle_proof(&hashes, 0).unwrap();
+        assert!(!verify_pi_hash_inclusion(
+            &root,
+            &levels[1][0],
+            &proof[1..],
+            0,
+            3
+        ));
+    }
+
+    #[test]
+    fn test_verify_pi_hash_inclusion_rejects_long_proof() {
+        let hashes = (1u8..=4).map(B256::repeat_byte).collect::<Vec<_>>();
+        let root = pi_hash_merkle_root(&hashes);
+        let mut proof = pi_hash_merkle_proof(&hashes, 1).unwrap();
+        proof.push(B256::ZERO);
+        assert!(!verify_pi_hash_inclusion(&root, &hashes[1], &proof, 1, 4));
+
+        // More siblings than bits in the index must not overflow the shift
+        let proof = vec![B256::ZERO; usize::BITS as usize + 1];
+        assert!(!verify_pi_hash_inclusion(
+            &root,
+            &hashes[1],
+            &proof,
+            1,
+            usize::MAX
+        ));
+    }
+
+    #[test]
+    fn test_compute_pi_hash() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../data/input-taiko-mainnet-192319.json");
+        let input: GuestInput =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+
+        let header = crate::builder::calculate_block_header(&input);
+        let expected = ProtocolInstance::new(&input, &header, VerifierType::None)
+            .unwrap()
+            .instance_hash();
+        assert_eq!(
+            compute_pi_hash(&input, VerifierType::None).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_compute_pi_hash_invalid_input() {
+        let mut input = GuestInput::default();
+        input.parent_header.state_root = B256::repeat_byte(1);
+        assert!(compute_pi_hash(&input, VerifierType::None).is_err());
+    }
+
+    #[test]
+    fn test_verify_pi_abi_consistency() {
+        let input = GuestInput::default();
+        let header = Header::default();
+        let mut abi = ProtocolInstance::new(&input, &header, VerifierType::SGX)
+            .unwrap()
+            .abi_encoded();
+        assert!(verify_pi_abi_consistency(&input, &header, &abi, VerifierType::SGX).is_ok());
+
+        abi[40] ^= 1;
+        assert!(verify_pi_abi_consistency(&input, &header, &abi, VerifierType::SGX).is_err());
+    }
 }
@@ -2,7 +2,11 @@ use reth_primitives::{ChainId, B256};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::input::{GuestInput, GuestOutput};
+use crate::{
+    consts::VerifierType,
+    input::{canonical_bytes, GuestInput, GuestOutput},
+    primitives::keccak::keccak,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum ProverError {
@@ -37,6 +41,168 @@ pub struct Proof {
     pub kzg_proof: Option<String>,
 }
 
+/// Relative cost of proving an input, used to balance work across provers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ProofCostEstimate {
+    /// Cost in prover specific units, only comparable between estimates of the same prover.
+    pub units: u64,
+    /// The number of transactions in the block.
+    pub tx_count: u64,
+    /// Whether the block data is posted in a blob.
+    pub blob_used: bool,
+}
+
+impl ProofCostEstimate {
+    /// Cost of a single transaction.
+    pub const TX_UNITS: u64 = 1;
+    /// Cost of processing the blob, roughly the price of a KZG commitment.
+    pub const BLOB_UNITS: u64 = 64;
+
+    pub fn new(tx_count: u64, blob_used: bool) -> Self {
+        let blob_units = if blob_used { Self::BLOB_UNITS } else { 0 };
+        Self {
+            units: tx_count * Self::TX_UNITS + blob_units,
+            tx_count,
+            blob_used,
+        }
+    }
+}
+
+/// Outcome of a single step of a proof verification.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckStatus {
+    Pass,
+    Fail(String),
+    /// Not run because an earlier check failed.
+    Skip,
+}
+
+/// A named verification step and its outcome.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationCheck {
+    pub name: String,
+    pub status: CheckStatus,
+}
+
+/// Every step of a proof verification with its outcome, built on success as well so
+/// auditors can see what was checked.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub checks: Vec<VerificationCheck>,
+}
+
+impl VerificationReport {
+    /// Records the outcome of the check `name`, passing its result through.
+    pub fn record<T>(&mut self, name: &str, result: ProverResult<T>) -> ProverResult<T> {
+        let status = match &result {
+            Ok(_) => CheckStatus::Pass,
+            Err(e) => CheckStatus::Fail(e.to_string()),
+        };
+        self.checks.push(VerificationCheck {
+            name: name.to_string(),
+            status,
+        });
+        result
+    }
+
+    /// Marks the checks of `names` that were not recorded as skipped.
+    pub fn skip_remaining(&mut self, names: &[&str]) {
+        for name in names {
+            if !self.checks.iter().any(|check| check.name == *name) {
+                self.checks.push(VerificationCheck {
+                    name: name.to_string(),
+                    status: CheckStatus::Skip,
+                });
+            }
+        }
+    }
+
+    /// Returns the first failed check as an error.
+    pub fn into_result(self) -> ProverResult<()> {
+        match self
+            .checks
+            .into_iter()
+            .find_map(|check| match check.status {
+                CheckStatus::Fail(reason) => Some(format!("{} check failed: {reason}", check.name)),
+                _ => None,
+            }) {
+            Some(error) => Err(ProverError::GuestError(error)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Gas of a zero calldata byte.
+const CALLDATA_ZERO_GAS: u64 = 4;
+/// Gas of a non-zero calldata byte.
+const CALLDATA_NONZERO_GAS: u64 = 16;
+/// Gas of the ecrecover precompile.
+const ECRECOVER_GAS: u64 = 3_000;
+/// Gas of the KZG point evaluation precompile.
+const POINT_EVALUATION_GAS: u64 = 50_000;
+
+/// Returns a rough estimate of the gas needed to verify the proof on chain.
+///
+/// Covers the calldata of the proof, the signature recovery of SGX proofs and, when a KZG
+/// proof is attached, a point evaluation. ZK verifiers additionally pay for their pairing
+/// checks, which depend on the verifier contract and are not included. Returns `None` when
+/// the proof is not hex encoded, use [`Prover::encode_for_verifier`] for other layouts.
+pub fn estimate_verification_gas(proof: &Proof, verifier: VerifierType) -> Option<u64> {
+    fn calldata_gas(data: &Option<String>) -> Option<u64> {
+        let Some(data) = data else {
+            return Some(0);
+        };
+        let bytes = hex::decode(data.trim_start_matches("0x")).ok()?;
+        Some(
+            bytes
+                .iter()
+                .map(|b| {
+                    if *b == 0 {
+                        CALLDATA_ZERO_GAS
+                    } else {
+                        CALLDATA_NONZERO_GAS
+                    }
+                })
+                .sum(),
+        )
+    }
+
+    let signature_gas = if verifier == VerifierType::SGX && proof.proof.is_some() {
+        ECRECOVER_GAS
+    } else {
+        0
+    };
+    let kzg_gas = if proof.kzg_proof.is_some() {
+        POINT_EVALUATION_GAS
+    } else {
+        0
+    };
+    Some(calldata_gas(&proof.proof)? + calldata_gas(&proof.kzg_proof)? + signature_gas + kzg_gas)
+}
+
+/// Returns the config with the keys of every object sorted.
+///
+/// `serde_json` keeps the insertion order of object keys, so equal configs can serialize to
+/// different bytes.
+fn canonical_config(config: &ProverConfig) -> ProverConfig {
+    match config {
+        serde_json::Value::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), canonical_config(value)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(values) => {
+            serde_json::Value::Array(values.iter().map(canonical_config).collect())
+        }
+        value => value.clone(),
+    }
+}
+
 #[async_trait::async_trait]
 pub trait IdWrite: Send {
     async fn store_id(&mut self, key: ProofKey, id: String) -> ProverResult<()>;
@@ -51,6 +217,9 @@ pub trait IdStore: IdWrite {
 
 #[allow(async_fn_in_trait)]
 pub trait Prover {
+    /// Stable name of the prover backend, used in logs and metrics labels.
+    const NAME: &'static str;
+
     async fn run(
         input: GuestInput,
         output: &GuestOutput,
@@ -59,4 +228,289 @@ pub trait Prover {
     ) -> ProverResult<Proof>;
 
     async fn cancel(proof_key: ProofKey, read: Box<&mut dyn IdStore>) -> ProverResult<()>;
+
+    /// Checks a proof produced by [`Prover::run`] for the given input.
+    ///
+    /// Backends that can verify their own proofs override this, the default rejects the proof.
+    async fn verify(
+        _input: &GuestInput,
+        _proof: &Proof,
+        _config: &ProverConfig,
+    ) -> ProverResult<()> {
+        Err(ProverError::GuestError(format!(
+            "{} prover does not support proof verification",
+            Self::NAME
+        )))
+    }
+
+    /// Returns the bytes to submit to the on-chain verifier for the proof.
+    ///
+    /// The default decodes a hex encoded proof, as produced by the SGX backend. Backends with
+    /// a different proof layout, like SP1 and RISC0, override it. Proofs for
+    /// [`VerifierType::None`] are not verified on chain and encode to nothing.
+    fn encode_for_verifier(proof: &Proof, verifier: VerifierType) -> ProverResult<Vec<u8>> {
+        if verifier == VerifierType::None {
+            return Ok(Vec::new());
+        }
+        let data = proof
+            .proof
+            .as_ref()
+            .ok_or_else(|| ProverError::GuestError(format!("{} proof is empty", Self::NAME)))?;
+        hex::decode(data.trim_start_matches("0x"))
+            .map_err(|e| ProverError::GuestError(format!("invalid {} proof: {e}", Self::NAME)))
+    }
+
+    /// Returns a stable key for a proof request so callers can deduplicate requests
+    /// before starting an expensive proof.
+    ///
+    /// The default hashes the canonical input together with the prover config, which also
+    /// carries the requested proof type. Config keys are sorted first, so the key does not
+    /// depend on the order the config was built in.
+    fn proof_key(input: &GuestInput, config: &ProverConfig) -> B256 {
+        let mut data = canonical_bytes(input);
+        data.extend(
+            serde_json::to_vec(&canonical_config(config)).expect("prover config is valid json"),
+        );
+        keccak(data).into()
+    }
+
+    /// Returns a cheap estimate of the resources needed to prove the input.
+    ///
+    /// The default is based on the transaction count and whether a blob is used; backends
+    /// with a different cost profile can override it.
+    fn estimate_cost(input: &GuestInput) -> ProofCostEstimate {
+        ProofCostEstimate::new(
+            input.block.body.len() as u64,
+            input.taiko.block_proposed.meta.blobUsed,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reth_primitives::TransactionSigned;
+
+    use super::*;
+    use crate::input::tests::reordered_storage_inputs;
+
+    struct TestProver;
+
+    impl Prover for TestProver {
+        const NAME: &'static str = "test";
+
+        async fn run(
+            input: GuestInput,
+            _output: &GuestOutput,
+            config: &ProverConfig,
+            _store: Option<&mut dyn IdWrite>,
+        ) -> ProverResult<Proof> {
+            Ok(Proof {
+                proof: Some(Self::proof_key(&input, config).to_string()),
+                ..Default::default()
+            })
+        }
+
+        async fn cancel(_proof_key: ProofKey, _read: Box<&mut dyn IdStore>) -> ProverResult<()> {
+            Ok(())
+        }
+
+        async fn verify(
+            input: &GuestInput,
+            proof: &Proof,
+            config: &ProverConfig,
+        ) -> ProverResult<()> {
+            let expected = Self::proof_key(input, config).to_string();
+            if proof.proof.as_ref() != Some(&expected) {
+                return Err(ProverError::GuestError("proof mismatch".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    struct UnverifiedProver;
+
+    impl Prover for UnverifiedProver {
+        const NAME: &'static str = "unverified";
+
+        async fn run(
+            _input: GuestInput,
+            _output: &GuestOutput,
+            _config: &ProverConfig,
+            _store: Option<&mut dyn IdWrite>,
+        ) -> ProverResult<Proof> {
+            Ok(Proof::default())
+        }
+
+        async fn cancel(_proof_key: ProofKey, _read: Box<&mut dyn IdStore>) -> ProverResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_checks_proof_from_run() {
+        let input = GuestInput::default();
+        let output = GuestOutput {
+            header: Default::default(),
+            hash: B256::ZERO,
+        };
+        let config = serde_json::json!({});
+
+        let mut proof = TestProver::run(input.clone(), &output, &config, None)
+            .await
+            .unwrap();
+        assert!(TestProver::verify(&input, &proof, &config).await.is_ok());
+
+        proof.proof = Some(B256::ZERO.to_string());
+        assert!(TestProver::verify(&input, &proof, &config).await.is_err());
+
+        assert!(UnverifiedProver::verify(&input, &Proof::default(), &config)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn verification_report_records_checks() {
+        let mut report = VerificationReport::default();
+        assert_eq!(report.record("first", Ok(1)).unwrap(), 1);
+        assert!(report
+            .record::<()>("second", Err(ProverError::GuestError("bad".to_string())))
+            .is_err());
+        report.skip_remaining(&["first", "second", "third"]);
+
+        let statuses = report
+            .checks
+            .iter()
+            .map(|check| (check.name.as_str(), check.status.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                ("first", CheckStatus::Pass),
+                (
+                    "second",
+                    CheckStatus::Fail("ProverError::GuestError `bad`".to_string())
+                ),
+                ("third", CheckStatus::Skip),
+            ]
+        );
+        assert!(report.into_result().is_err());
+        assert!(VerificationReport::default().into_result().is_ok());
+    }
+
+    #[test]
+    fn proof_key_is_stable() {
+        let config = serde_json::json!({ "proof_type": "native" });
+        let input = GuestInput::default();
+        let key = TestProver::proof_key(&input, &config);
+        assert_eq!(key, TestProver::proof_key(&input.clone(), &config));
+
+        let mut changed = input.clone();
+        changed.taiko.tx_data = vec![1];
+        assert_ne!(key, TestProver::proof_key(&changed, &config));
+
+        let other_config = serde_json::json!({ "proof_type": "sgx" });
+        assert_ne!(key, TestProver::proof_key(&input, &other_config));
+    }
+
+    #[test]
+    fn proof_key_ignores_config_key_order() {
+        let mut a = serde_json::Map::new();
+        a.insert("proof_type".to_string(), serde_json::json!("sgx"));
+        a.insert(
+            "sgx".to_string(),
+            serde_json::json!({ "instance_id": 1, "setup": false, "prove": true }),
+        );
+        let mut b = serde_json::Map::new();
+        b.insert(
+            "sgx".to_string(),
+            serde_json::json!({ "prove": true, "setup": false, "instance_id": 1 }),
+        );
+        b.insert("proof_type".to_string(), serde_json::json!("sgx"));
+
+        let input = GuestInput::default();
+        assert_eq!(
+            TestProver::proof_key(&input, &a.into()),
+            TestProver::proof_key(&input, &b.into())
+        );
+    }
+
+    #[test]
+    fn estimate_cost_grows_with_transactions() {
+        let mut input = GuestInput::default();
+        let empty = TestProver::estimate_cost(&input);
+
+        input.block.body = vec![TransactionSigned::default(); 4];
+        let with_txs = TestProver::estimate_cost(&input);
+        assert!(with_txs.units > empty.units);
+
+        input.taiko.block_proposed.meta.blobUsed = true;
+        assert!(TestProver::estimate_cost(&input).units > with_txs.units);
+    }
+
+    #[test]
+    fn proof_key_ignores_storage_order() {
+        let (a, b) = reordered_storage_inputs();
+        let config = serde_json::json!({});
+        assert_eq!(
+            TestProver::proof_key(&a, &config),
+            TestProver::proof_key(&b, &config)
+        );
+    }
+
+    #[test]
+    fn verification_gas_grows_with_proof_size() {
+        let proof = |data: &str| Proof {
+            proof: Some(data.to_string()),
+            ..Default::default()
+        };
+        let gas = |proof: &Proof| estimate_verification_gas(proof, VerifierType::SGX).unwrap();
+        assert_eq!(gas(&Proof::default()), 0);
+
+        let small = gas(&proof("0x0102"));
+        assert_eq!(small, ECRECOVER_GAS + 2 * CALLDATA_NONZERO_GAS);
+        assert!(gas(&proof(&"01".repeat(89))) > small);
+
+        let with_kzg = Proof {
+            kzg_proof: Some("0x00".to_string()),
+            ..proof("0x0102")
+        };
+        assert_eq!(
+            gas(&with_kzg),
+            small + POINT_EVALUATION_GAS + CALLDATA_ZERO_GAS
+        );
+    }
+
+    #[test]
+    fn verification_gas_only_recovers_sgx_signatures() {
+        let proof = Proof {
+            proof: Some("0x0102".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            estimate_verification_gas(&proof, VerifierType::RISC0),
+            Some(2 * CALLDATA_NONZERO_GAS)
+        );
+
+        let json = Proof {
+            proof: Some(r#"{"proof":"0x0102"}"#.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(estimate_verification_gas(&json, VerifierType::SP1), None);
+    }
+
+    #[test]
+    fn encode_for_verifier_decodes_proof() {
+        let proof = Proof {
+            proof: Some("0x000102ff".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            TestProver::encode_for_verifier(&proof, VerifierType::SGX).unwrap(),
+            vec![0x00, 0x01, 0x02, 0xff]
+        );
+        assert!(TestProver::encode_for_verifier(&proof, VerifierType::None)
+            .unwrap()
+            .is_empty());
+        assert!(TestProver::encode_for_verifier(&Proof::default(), VerifierType::SP1).is_err());
+    }
 }
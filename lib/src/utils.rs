@@ -1,14 +1,24 @@
-use std::io::{Read, Write};
+use std::{
+    collections::HashSet,
+    io::{Read, Write},
+};
 
-use alloy_rlp::Decodable;
+use alloy_rlp::{Decodable, Header};
 use anyhow::Result;
 use libflate::zlib::{Decoder as zlibDecoder, Encoder as zlibEncoder};
-use reth_primitives::TransactionSigned;
+use reth_primitives::{
+    TransactionSigned, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID,
+};
 use tracing::warn;
 
-use crate::consts::{ChainSpec, Network};
 #[cfg(not(feature = "std"))]
 use crate::no_std::*;
+use crate::{
+    consts::{ChainSpec, Network},
+    primitives::eip4844::{
+        Eip4844Error, BYTES_PER_BLOB, BYTES_PER_FIELD_ELEMENT, FIELD_ELEMENTS_PER_BLOB,
+    },
+};
 
 pub fn decode_transactions(tx_list: &[u8]) -> Vec<TransactionSigned> {
     #[allow(clippy::useless_asref)]
@@ -19,6 +29,111 @@ pub fn decode_transactions(tx_list: &[u8]) -> Vec<TransactionSigned> {
     })
 }
 
+/// Decodes the tx list like [`decode_transactions`], but rejects lists holding more than
+/// `max_txs` transactions before any of them is decoded.
+pub fn decode_transactions_with_limit(
+    tx_list: &[u8],
+    max_txs: usize,
+) -> Result<Vec<TransactionSigned>, Eip4844Error> {
+    let mut count = 0;
+    for_each_rlp_list_item(tx_list, |_, _, _| {
+        count += 1;
+        Ok(count <= max_txs)
+    })?;
+    if count > max_txs {
+        return Err(Eip4844Error::TooManyTransactions { max: max_txs });
+    }
+    decode_tx_list_items(tx_list)
+}
+
+/// Checks that the decompressed tx list is a valid RLP list of transactions, reporting the
+/// byte offset of the first transaction that fails to decode.
+pub fn validate_tx_list(tx_list: &[u8]) -> Result<(), Eip4844Error> {
+    decode_tx_list_items(tx_list).map(|_| ())
+}
+
+/// Checks that the tx list only holds transaction types the builder can execute, so
+/// unsupported transactions (e.g. EIP-7702 set-code transactions) are reported up front
+/// instead of failing deep inside decoding.
+pub fn check_transaction_types(tx_list: &[u8]) -> Result<(), Eip4844Error> {
+    for_each_rlp_list_item(tx_list, |_, header, payload| {
+        // legacy transactions are rlp lists, typed ones are strings of `type || rlp(tx)`
+        if !header.list {
+            let tx_type = payload.first().copied().unwrap_or_default();
+            if !SUPPORTED_TX_TYPES.contains(&tx_type) {
+                return Err(Eip4844Error::UnsupportedTransactionType(tx_type));
+            }
+        }
+        Ok(true)
+    })
+}
+
+/// Checks that no transaction appears twice in the decoded tx list.
+pub fn check_duplicate_transactions(
+    transactions: &[TransactionSigned],
+) -> Result<(), Eip4844Error> {
+    let mut seen = HashSet::with_capacity(transactions.len());
+    for tx in transactions {
+        if !seen.insert(tx.hash()) {
+            return Err(Eip4844Error::DuplicateTransaction(tx.hash()));
+        }
+    }
+    Ok(())
+}
+
+const SUPPORTED_TX_TYPES: [u8; 3] = [EIP2930_TX_TYPE_ID, EIP1559_TX_TYPE_ID, EIP4844_TX_TYPE_ID];
+
+fn decode_tx_list_items(tx_list: &[u8]) -> Result<Vec<TransactionSigned>, Eip4844Error> {
+    let mut transactions = Vec::new();
+    for_each_rlp_list_item(tx_list, |offset, _, _| {
+        let tx = TransactionSigned::decode(&mut &tx_list[offset..]).map_err(|e| {
+            Eip4844Error::InvalidTransactionList {
+                offset,
+                reason: e.to_string(),
+            }
+        })?;
+        transactions.push(tx);
+        Ok(true)
+    })?;
+    Ok(transactions)
+}
+
+/// Walks the items of the RLP list `buf` without decoding them, calling `f` with the byte
+/// offset of every item, its header and its payload. Stops once `f` returns `false`.
+fn for_each_rlp_list_item(
+    buf: &[u8],
+    mut f: impl FnMut(usize, &Header, &[u8]) -> Result<bool, Eip4844Error>,
+) -> Result<(), Eip4844Error> {
+    let invalid = |offset: usize, e: alloy_rlp::Error| Eip4844Error::InvalidTransactionList {
+        offset,
+        reason: e.to_string(),
+    };
+
+    let mut rest = buf;
+    let header = Header::decode(&mut rest).map_err(|e| invalid(0, e))?;
+    if !header.list {
+        return Err(invalid(0, alloy_rlp::Error::UnexpectedString));
+    }
+    if rest.len() < header.payload_length {
+        return Err(invalid(0, alloy_rlp::Error::InputTooShort));
+    }
+
+    let mut offset = buf.len() - rest.len();
+    let end = offset + header.payload_length;
+    while offset < end {
+        let mut item = &buf[offset..end];
+        let item_header = Header::decode(&mut item).map_err(|e| invalid(offset, e))?;
+        if item.len() < item_header.payload_length {
+            return Err(invalid(offset, alloy_rlp::Error::InputTooShort));
+        }
+        if !f(offset, &item_header, &item[..item_header.payload_length])? {
+            break;
+        }
+        offset = end - item.len() + item_header.payload_length;
+    }
+    Ok(())
+}
+
 // leave a simply fn in case of more checks in future
 fn validate_calldata_tx_list(tx_list: &[u8]) -> bool {
     tx_list.len() <= CALL_DATA_CAPACITY
@@ -63,6 +178,15 @@ pub fn generate_transactions(
 ) -> Vec<TransactionSigned> {
     // Decode the tx list from the raw data posted onchain
     let tx_list = get_tx_list(chain_spec, is_blob_data, tx_list);
+    // Unsupported transaction types make the whole list decode to an empty block, other
+    // invalid lists are already reported by `decode_transactions`
+    if !tx_list.is_empty() {
+        if let Err(e @ Eip4844Error::UnsupportedTransactionType(_)) =
+            check_transaction_types(&tx_list)
+        {
+            warn!("generate_transactions: {e}");
+        }
+    }
     // Decode the transactions from the tx list
     let mut transactions = decode_transactions(&tx_list);
     // Add the anchor tx at the start of the list
@@ -72,14 +196,13 @@ pub fn generate_transactions(
     transactions
 }
 
-const BLOB_FIELD_ELEMENT_NUM: usize = 4096;
-const BLOB_FIELD_ELEMENT_BYTES: usize = 32;
-const BLOB_DATA_CAPACITY: usize = BLOB_FIELD_ELEMENT_NUM * BLOB_FIELD_ELEMENT_BYTES;
 // max call data bytes
-const CALL_DATA_CAPACITY: usize = BLOB_FIELD_ELEMENT_NUM * (BLOB_FIELD_ELEMENT_BYTES - 1);
+const CALL_DATA_CAPACITY: usize = FIELD_ELEMENTS_PER_BLOB * (BYTES_PER_FIELD_ELEMENT - 1);
 const BLOB_VERSION_OFFSET: usize = 1;
 const BLOB_ENCODING_VERSION: u8 = 0;
-const MAX_BLOB_DATA_SIZE: usize = (4 * 31 + 3) * 1024 - 4;
+// every round decodes 4 field elements into 127 bytes
+const BLOB_ENCODING_ROUNDS: usize = FIELD_ELEMENTS_PER_BLOB / 4;
+const MAX_BLOB_DATA_SIZE: usize = (4 * 31 + 3) * BLOB_ENCODING_ROUNDS - 4;
 
 // decoding https://github.com/ethereum-optimism/optimism/blob/develop/op-service/eth/blob.go
 fn decode_blob_data(blob_buf: &[u8]) -> Vec<u8> {
@@ -118,7 +241,7 @@ fn decode_blob_data(blob_buf: &[u8]) -> Vec<u8> {
 
     // in each remaining round we decode 4 field elements (128 bytes) of the input into 127
     // bytes of output
-    for _ in 1..1024 {
+    for _ in 1..BLOB_ENCODING_ROUNDS {
         if opos < output_len {
             for encoded_byte_j in &mut encoded_byte {
                 // save the first byte of each field element for later re-assembly
@@ -136,7 +259,7 @@ fn decode_blob_data(blob_buf: &[u8]) -> Vec<u8> {
             return Vec::new();
         }
     }
-    for itailing in blob_buf.iter().take(BLOB_DATA_CAPACITY).skip(ipos) {
+    for itailing in blob_buf.iter().take(BYTES_PER_BLOB).skip(ipos) {
         if *itailing != 0 {
             return Vec::new();
         }
@@ -191,3 +314,84 @@ pub fn zlib_compress_data(data: &[u8]) -> Result<Vec<u8>> {
     let res = encoder.finish().into_result()?;
     Ok(res.clone())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_transactions_limit() {
+        let tx_list = alloy_rlp::encode(vec![TransactionSigned::default(); 3]);
+        assert_eq!(
+            decode_transactions_with_limit(&tx_list, 3).unwrap().len(),
+            3
+        );
+        assert!(matches!(
+            decode_transactions_with_limit(&tx_list, 2),
+            Err(Eip4844Error::TooManyTransactions { max: 2 })
+        ));
+        assert!(matches!(
+            decode_transactions_with_limit(&tx_list[..tx_list.len() - 1], 3),
+            Err(Eip4844Error::InvalidTransactionList { .. })
+        ));
+    }
+
+    #[test]
+    fn check_unsupported_transaction_types() {
+        let tx_list = alloy_rlp::encode(vec![TransactionSigned::default(); 2]);
+        assert!(check_transaction_types(&tx_list).is_ok());
+
+        // An EIP-7702 set-code transaction, only the type byte matters here
+        let set_code_tx = alloy_rlp::encode(&[0x04u8, 0xc0][..]);
+        let mut payload = alloy_rlp::encode(TransactionSigned::default());
+        payload.extend(set_code_tx);
+        let mut tx_list = Vec::new();
+        Header {
+            list: true,
+            payload_length: payload.len(),
+        }
+        .encode(&mut tx_list);
+        tx_list.extend(payload);
+        assert!(matches!(
+            check_transaction_types(&tx_list),
+            Err(Eip4844Error::UnsupportedTransactionType(0x04))
+        ));
+    }
+
+    #[test]
+    fn validate_tx_list_reports_offset() {
+        let tx_list = alloy_rlp::encode(vec![TransactionSigned::default(); 2]);
+        assert!(validate_tx_list(&tx_list).is_ok());
+
+        // Corrupt the first field of the second transaction
+        let tx_len = alloy_rlp::encode(TransactionSigned::default()).len();
+        let second_tx = tx_list.len() - tx_len;
+        let mut corrupt = tx_list.clone();
+        corrupt[second_tx + 1] = 0xff;
+        assert!(matches!(
+            validate_tx_list(&corrupt),
+            Err(Eip4844Error::InvalidTransactionList { offset, .. }) if offset == second_tx
+        ));
+
+        assert!(matches!(
+            validate_tx_list(&[0x80]),
+            Err(Eip4844Error::InvalidTransactionList { offset: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn duplicate_transactions() {
+        let mut other = TransactionSigned::default();
+        other.transaction.set_nonce(1);
+        other.hash = other.recalculate_hash();
+        let unique = vec![TransactionSigned::default(), other.clone()];
+        assert!(check_duplicate_transactions(&unique).is_ok());
+
+        let tx_list = alloy_rlp::encode(vec![other.clone(), TransactionSigned::default(), other]);
+        let transactions = decode_transactions_with_limit(&tx_list, 3).unwrap();
+        assert!(matches!(
+            check_duplicate_transactions(&transactions),
+            Err(Eip4844Error::DuplicateTransaction(hash)) if hash == transactions[0].hash()
+        ));
+    }
+}
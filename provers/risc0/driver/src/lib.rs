@@ -4,6 +4,7 @@ use alloy_primitives::B256;
 use hex::ToHex;
 use log::warn;
 use raiko_lib::{
+    consts::VerifierType,
     input::{GuestInput, GuestOutput},
     prover::{IdStore, IdWrite, Proof, ProofKey, Prover, ProverConfig, ProverError, ProverResult},
 };
@@ -53,6 +54,8 @@ pub struct Risc0Prover;
 const RISC0_PROVER_CODE: u8 = 3;
 
 impl Prover for Risc0Prover {
+    const NAME: &'static str = "risc0";
+
     async fn run(
         input: GuestInput,
         output: &GuestOutput,
@@ -119,6 +122,23 @@ impl Prover for Risc0Prover {
         id_store.remove_id(key).await?;
         Ok(())
     }
+
+    /// Only the `0x` prefixed Groth16 output of snark mode can be verified on chain, outside
+    /// of snark mode the proof is the bare hex encoded journal.
+    fn encode_for_verifier(proof: &Proof, verifier: VerifierType) -> ProverResult<Vec<u8>> {
+        if verifier == VerifierType::None {
+            return Ok(Vec::new());
+        }
+        let data = proof
+            .proof
+            .as_ref()
+            .ok_or_else(|| ProverError::GuestError("Risc0: proof is empty".to_owned()))?;
+        let snark = data.strip_prefix("0x").ok_or_else(|| {
+            ProverError::GuestError("Risc0: only snark proofs can be verified on chain".to_owned())
+        })?;
+        hex::decode(snark)
+            .map_err(|e| ProverError::GuestError(format!("Risc0: invalid proof: {e}")))
+    }
 }
 
 #[cfg(test)]
@@ -135,4 +155,19 @@ mod test {
         let receipt = prover.prove(env, TEST_RISC0_GUEST_ELF).unwrap();
         receipt.receipt.verify(TEST_RISC0_GUEST_ID).unwrap();
     }
+
+    #[test]
+    fn test_encode_for_verifier() {
+        let proof = |data: &str| Proof {
+            proof: Some(data.to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Risc0Prover::encode_for_verifier(&proof("0x0102"), VerifierType::RISC0).unwrap(),
+            vec![1, 2]
+        );
+        // A journal from non-snark mode is not an on-chain proof
+        assert!(Risc0Prover::encode_for_verifier(&proof("0102"), VerifierType::RISC0).is_err());
+        assert!(Risc0Prover::encode_for_verifier(&Proof::default(), VerifierType::RISC0).is_err());
+    }
 }
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use clap::{Args, Parser, Subcommand};
+use secp256k1::PublicKey;
 
 const DEFAULT_RAIKO_USER_CONFIG_SUBDIR_PATH: &str = ".config/raiko";
 
@@ -23,6 +24,19 @@ pub enum Command {
     Bootstrap,
     /// Check if bootstrap is readable
     Check,
+    /// Print the verifier allow-list update needed to rotate from the current instance keys
+    /// to the next ones.
+    AllowListUpdate(AllowListUpdateArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct AllowListUpdateArgs {
+    /// Public keys of the instances currently registered, comma separated.
+    #[clap(long, value_delimiter = ',')]
+    pub current: Vec<PublicKey>,
+    /// Public keys of the instances to register after the rotation, comma separated.
+    #[clap(long, value_delimiter = ',')]
+    pub next: Vec<PublicKey>,
 }
 
 #[derive(Debug, Args)]
@@ -7,6 +7,7 @@ use clap::Parser;
 use crate::{
     app_args::{App, Command},
     one_shot::{bootstrap, load_bootstrap, one_shot},
+    signature::allow_list_update,
 };
 
 mod app_args;
@@ -31,6 +32,14 @@ pub async fn main() -> Result<()> {
             load_bootstrap(&args.global_opts.secrets_dir)
                 .map_err(|err| anyhow!("check booststrap failed: {err}"))?;
         }
+        Command::AllowListUpdate(update_args) => {
+            let update = allow_list_update(&update_args.current, &update_args.next);
+            let data = serde_json::json!({
+                "add": update.add,
+                "remove": update.remove,
+            });
+            println!("{data}");
+        }
     }
 
     Ok(())
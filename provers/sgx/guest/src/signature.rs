@@ -58,6 +58,35 @@ pub fn public_key(secret: &SecretKey) -> PublicKey {
     PublicKey::from_secret_key_global(secret)
 }
 
+/// Changes to a verifier's allow-list of instance addresses.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AllowListUpdate {
+    pub add: Vec<Address>,
+    pub remove: Vec<Address>,
+}
+
+/// Returns the allow-list update needed when rotating from the `current` keys to the `next`
+/// ones. Addresses present in both stay registered and show up in neither list.
+pub fn allow_list_update(current: &[PublicKey], next: &[PublicKey]) -> AllowListUpdate {
+    let current = current
+        .iter()
+        .map(public_key_to_address)
+        .collect::<Vec<_>>();
+    let next = next.iter().map(public_key_to_address).collect::<Vec<_>>();
+    AllowListUpdate {
+        add: next
+            .iter()
+            .filter(|address| !current.contains(address))
+            .copied()
+            .collect(),
+        remove: current
+            .iter()
+            .filter(|address| !next.contains(address))
+            .copied()
+            .collect(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -82,4 +111,24 @@ mod tests {
         println!("Public address: {pub_addr}");
         println!("Proof public address: {proof_addr}");
     }
+
+    #[test]
+    fn rotation_allow_list_update() {
+        let kept = generate_key().public_key();
+        let retired = generate_key().public_key();
+        let rotated_in = generate_key().public_key();
+
+        let update = allow_list_update(&[kept, retired], &[kept, rotated_in]);
+        assert_eq!(
+            update,
+            AllowListUpdate {
+                add: vec![public_key_to_address(&rotated_in)],
+                remove: vec![public_key_to_address(&retired)],
+            }
+        );
+        assert_eq!(
+            allow_list_update(&[kept], &[kept]),
+            AllowListUpdate::default()
+        );
+    }
 }
@@ -10,8 +10,15 @@ use std::{
 
 use once_cell::sync::Lazy;
 use raiko_lib::{
+    builder::try_calculate_block_header,
+    consts::VerifierType,
     input::{GuestInput, GuestOutput},
-    prover::{IdStore, IdWrite, Proof, ProofKey, Prover, ProverConfig, ProverError, ProverResult},
+    primitives::{hex, Address, Signature, B256},
+    protocol_instance::ProtocolInstance,
+    prover::{
+        IdStore, IdWrite, Proof, ProofKey, Prover, ProverConfig, ProverError, ProverResult,
+        VerificationReport,
+    },
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -54,6 +61,9 @@ impl From<SgxResponse> for Proof {
     }
 }
 
+/// Length of the proof submitted to the SGX verifier: 4b(id)+20b(pubkey)+65b(signature).
+pub const SGX_PROOF_LEN: usize = 89;
+
 pub const ELF_NAME: &str = "sgx-guest";
 pub const CONFIG: &str = if cfg!(feature = "docker_build") {
     "../provers/sgx/config"
@@ -67,6 +77,8 @@ static PRIVATE_KEY: Lazy<OnceCell<PathBuf>> = Lazy::new(OnceCell::new);
 pub struct SgxProver;
 
 impl Prover for SgxProver {
+    const NAME: &'static str = "sgx";
+
     async fn run(
         input: GuestInput,
         _output: &GuestOutput,
@@ -151,6 +163,118 @@ impl Prover for SgxProver {
     async fn cancel(_proof_key: ProofKey, _read: Box<&mut dyn IdStore>) -> ProverResult<()> {
         Ok(())
     }
+
+    /// Runs the checks of [`SgxProver::verification_report`] and returns the first failure.
+    async fn verify(input: &GuestInput, proof: &Proof, config: &ProverConfig) -> ProverResult<()> {
+        Self::verification_report(input, proof, config).into_result()
+    }
+}
+
+/// The checks performed by [`SgxProver::verification_report`], in order.
+pub const SGX_VERIFICATION_CHECKS: [&str; 5] = [
+    "expected_instance",
+    "decode_proof",
+    "pi_hash",
+    "instance_address",
+    "signature",
+];
+
+impl SgxProver {
+    /// Verifies the proof step by step, reporting the outcome of every check.
+    ///
+    /// Rebuilds the block, binds the instance address embedded in the proof to the public
+    /// input like the guest does and checks that the instance signed the resulting hash.
+    /// The embedded address must be the registered instance given as `sgx.instance_address`
+    /// in the config, otherwise any key could sign a valid looking proof. Checks after the
+    /// first failure are skipped.
+    pub fn verification_report(
+        input: &GuestInput,
+        proof: &Proof,
+        config: &ProverConfig,
+    ) -> VerificationReport {
+        let mut report = VerificationReport::default();
+        let _ = run_sgx_checks(&mut report, input, proof, config);
+        report.skip_remaining(&SGX_VERIFICATION_CHECKS);
+        report
+    }
+}
+
+fn run_sgx_checks(
+    report: &mut VerificationReport,
+    input: &GuestInput,
+    proof: &Proof,
+    config: &ProverConfig,
+) -> ProverResult<()> {
+    let expected = report.record("expected_instance", expected_instance(config))?;
+    let proof = report.record("decode_proof", decode_sgx_proof(proof))?;
+    let pi_hash = report.record("pi_hash", sgx_pi_hash(input, sgx_proof_instance(&proof)))?;
+    report.record("instance_address", check_sgx_instance(&proof, expected))?;
+    report.record("signature", verify_sgx_signature(&proof, pi_hash))
+}
+
+fn expected_instance(config: &ProverConfig) -> ProverResult<Address> {
+    let expected = config
+        .get("sgx")
+        .and_then(|sgx| sgx.get("instance_address"))
+        .ok_or_else(|| ProverError::GuestError("sgx.instance_address not provided".to_owned()))?;
+    Ok(Address::deserialize(expected)?)
+}
+
+fn decode_sgx_proof(proof: &Proof) -> ProverResult<[u8; SGX_PROOF_LEN]> {
+    let data = proof
+        .proof
+        .as_ref()
+        .ok_or_else(|| ProverError::GuestError("SGX proof is empty".to_owned()))?;
+    let bytes = hex::decode(data.trim_start_matches("0x"))
+        .map_err(|e| ProverError::GuestError(format!("invalid SGX proof: {e}")))?;
+    bytes.try_into().map_err(|bytes: Vec<u8>| {
+        ProverError::GuestError(format!(
+            "invalid SGX proof length: expected {SGX_PROOF_LEN}, got {}",
+            bytes.len()
+        ))
+    })
+}
+
+/// Rebuilds the block and returns the public input hash bound to `instance`.
+fn sgx_pi_hash(input: &GuestInput, instance: Address) -> ProverResult<B256> {
+    let header = try_calculate_block_header(input)
+        .map_err(|e| ProverError::GuestError(format!("failed to build block: {e}")))?;
+    Ok(ProtocolInstance::new(input, &header, VerifierType::SGX)
+        .map_err(|e| ProverError::GuestError(e.to_string()))?
+        .sgx_instance(instance)
+        .instance_hash())
+}
+
+/// Returns the instance address embedded in the proof.
+fn sgx_proof_instance(proof: &[u8; SGX_PROOF_LEN]) -> Address {
+    Address::from_slice(&proof[4..24])
+}
+
+/// Checks that the proof is for the `expected` instance.
+fn check_sgx_instance(proof: &[u8; SGX_PROOF_LEN], expected: Address) -> ProverResult<()> {
+    let instance = sgx_proof_instance(proof);
+    if instance != expected {
+        return Err(ProverError::GuestError(format!(
+            "SGX proof is for instance {instance}, expected {expected}"
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that the signature in the proof over `pi_hash` was made by the embedded instance.
+fn verify_sgx_signature(proof: &[u8; SGX_PROOF_LEN], pi_hash: B256) -> ProverResult<()> {
+    let signature = Signature::try_from(&proof[24..])
+        .map_err(|e| ProverError::GuestError(format!("invalid SGX signature: {e}")))?;
+    let signer = signature
+        .recover_address_from_prehash(&pi_hash)
+        .map_err(|e| ProverError::GuestError(format!("invalid SGX signature: {e}")))?;
+    let instance = sgx_proof_instance(proof);
+    if signer != instance {
+        return Err(ProverError::GuestError(format!(
+            "SGX proof signed by {signer}, expected instance {instance}"
+        )));
+    }
+    Ok(())
 }
 
 async fn setup(cur_dir: &Path, direct_mode: bool) -> ProverResult<(), String> {
@@ -348,3 +472,88 @@ fn handle_output(output: &Output, name: &str) -> ProverResult<(), String> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+    use raiko_lib::prover::CheckStatus;
+
+    use super::*;
+
+    /// Builds a proof the way the guest's one-shot command does.
+    fn sign_proof(signer: &PrivateKeySigner, instance_id: u32, pi_hash: B256) -> Proof {
+        let signature = signer.sign_hash_sync(&pi_hash).unwrap();
+        let mut proof = Vec::with_capacity(SGX_PROOF_LEN);
+        proof.extend(instance_id.to_be_bytes());
+        proof.extend(signer.address());
+        proof.extend(signature.as_bytes());
+        Proof {
+            proof: Some(format!("0x{}", hex::encode(proof))),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../../data/input-taiko-mainnet-192319.json");
+        let input: GuestInput =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let signer = PrivateKeySigner::from_bytes(&B256::repeat_byte(7)).unwrap();
+
+        let header = try_calculate_block_header(&input).unwrap();
+        let pi = ProtocolInstance::new(&input, &header, VerifierType::SGX).unwrap();
+        let pi_hash = |instance| pi.clone().sgx_instance(instance).instance_hash();
+        let proof = sign_proof(&signer, 1, pi_hash(signer.address()));
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let verify = |proof: &Proof, config: &ProverConfig| {
+            rt.block_on(SgxProver::verify(&input, proof, config))
+        };
+        let config = serde_json::json!({ "sgx": { "instance_address": signer.address() } });
+        assert!(verify(&proof, &config).is_ok());
+
+        // The expected instance is required
+        assert!(verify(&proof, &serde_json::json!({})).is_err());
+
+        // A signature over another block does not verify
+        let tampered = sign_proof(&signer, 1, B256::repeat_byte(1));
+        assert!(verify(&tampered, &config).is_err());
+
+        // Neither does a correctly signed proof from an unknown instance
+        let foreign = PrivateKeySigner::from_bytes(&B256::repeat_byte(8)).unwrap();
+        let foreign_proof = sign_proof(&foreign, 1, pi_hash(foreign.address()));
+        assert!(verify(&foreign_proof, &config).is_err());
+
+        // Or a proof claiming the expected instance, signed by another key
+        let mut bytes = decode_sgx_proof(&foreign_proof).unwrap();
+        bytes[4..24].copy_from_slice(signer.address().as_slice());
+        assert!(check_sgx_instance(&bytes, signer.address()).is_ok());
+        assert!(verify_sgx_signature(&bytes, pi_hash(signer.address())).is_err());
+
+        // The report lists every check, on success as well
+        let statuses = |proof: &Proof| {
+            SgxProver::verification_report(&input, proof, &config)
+                .checks
+                .into_iter()
+                .map(|check| (check.name, check.status))
+                .collect::<Vec<_>>()
+        };
+        let names = SGX_VERIFICATION_CHECKS.map(str::to_string);
+        assert_eq!(
+            statuses(&proof),
+            names.clone().map(|name| (name, CheckStatus::Pass)).to_vec()
+        );
+        let report = statuses(&foreign_proof);
+        assert_eq!(
+            report
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>(),
+            names.to_vec()
+        );
+        assert!(matches!(report[3].1, CheckStatus::Fail(_)));
+        assert_eq!(report[4].1, CheckStatus::Skip);
+    }
+}
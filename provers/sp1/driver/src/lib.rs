@@ -3,6 +3,7 @@
 
 use once_cell::sync::Lazy;
 use raiko_lib::{
+    consts::VerifierType,
     input::{GuestInput, GuestOutput},
     prover::{IdStore, IdWrite, Proof, ProofKey, Prover, ProverConfig, ProverError, ProverResult},
     Measurement,
@@ -15,7 +16,9 @@ use sp1_sdk::{
     network::client::NetworkClient,
     proto::network::{ProofMode, UnclaimReason},
 };
-use sp1_sdk::{HashableKey, ProverClient, SP1Stdin, SP1VerifyingKey};
+use sp1_sdk::{
+    HashableKey, ProverClient, SP1Proof, SP1ProofWithPublicValues, SP1Stdin, SP1VerifyingKey,
+};
 use std::env;
 use std::fs;
 use std::path::PathBuf;
@@ -82,6 +85,8 @@ pub struct Sp1Response {
 pub struct Sp1Prover;
 
 impl Prover for Sp1Prover {
+    const NAME: &'static str = "sp1";
+
     async fn run(
         input: GuestInput,
         output: &GuestOutput,
@@ -170,6 +175,25 @@ impl Prover for Sp1Prover {
         id_store.remove_id(key).await?;
         Ok(())
     }
+
+    /// The proof is the json serialized [`SP1ProofWithPublicValues`], only PlonK proofs can be
+    /// verified on chain.
+    fn encode_for_verifier(proof: &Proof, verifier: VerifierType) -> ProverResult<Vec<u8>> {
+        if verifier == VerifierType::None {
+            return Ok(Vec::new());
+        }
+        let data = proof
+            .proof
+            .as_ref()
+            .ok_or_else(|| ProverError::GuestError("Sp1: proof is empty".to_owned()))?;
+        let proof: SP1ProofWithPublicValues = serde_json::from_str(data)?;
+        if !matches!(proof.proof, SP1Proof::Plonk(_)) {
+            return Err(ProverError::GuestError(
+                "Sp1: only PlonK proofs can be verified on chain".to_owned(),
+            ));
+        }
+        Ok(proof.bytes())
+    }
 }
 
 fn get_env_mock() -> ProverMode {
@@ -236,10 +260,7 @@ struct RaikoProofFixture {
     proof: String,
 }
 
-pub fn verify_sol(
-    vk: SP1VerifyingKey,
-    mut proof: sp1_sdk::SP1ProofWithPublicValues,
-) -> ProverResult<()> {
+pub fn verify_sol(vk: SP1VerifyingKey, mut proof: SP1ProofWithPublicValues) -> ProverResult<()> {
     assert!(VERIFIER.is_ok());
 
     // Deserialize the public values.
@@ -321,4 +342,28 @@ mod test {
             .verify(&proof, &vk)
             .expect("Sp1: verification failed");
     }
+
+    #[test]
+    fn test_encode_for_verifier() {
+        let client = ProverClient::mock();
+        let (pk, _vk) = client.setup(TEST_ELF);
+        let as_proof = |proof: &SP1ProofWithPublicValues| Proof {
+            proof: serde_json::to_string(proof).ok(),
+            ..Default::default()
+        };
+
+        let plonk = client.prove(&pk, SP1Stdin::new()).plonk().run().unwrap();
+        assert_eq!(
+            Sp1Prover::encode_for_verifier(&as_proof(&plonk), VerifierType::SP1).unwrap(),
+            plonk.bytes()
+        );
+
+        let core = client.prove(&pk, SP1Stdin::new()).run().unwrap();
+        assert!(Sp1Prover::encode_for_verifier(&as_proof(&core), VerifierType::SP1).is_err());
+        assert!(
+            Sp1Prover::encode_for_verifier(&as_proof(&core), VerifierType::None)
+                .unwrap()
+                .is_empty()
+        );
+    }
 }